@@ -1,5 +1,98 @@
+use crate::prelude::{VrmUsageViolation, VrmValidationReport};
+use bevy::asset::AssetLoadError;
+use bevy::gltf::GltfError;
+use std::sync::Arc;
+use thiserror::Error;
+
 pub type AppResult<T = ()> = Result<T, anyhow::Error>;
 
+/// The errors that can occur while loading VRM and VRMA.
+///
+/// When spawning fails, this error is delivered through [`VrmLoadFailed`](crate::prelude::VrmLoadFailed)
+/// or [`VrmaLoadFailed`](crate::prelude::VrmaLoadFailed).
+#[derive(Debug, Error)]
+pub enum VrmError {
+    /// The glTF could not be loaded.
+    #[error(transparent)]
+    Gltf(Box<GltfError>),
+
+    /// The asset failed to load.
+    ///
+    /// Since the extensions are validated while loading, this wraps most of the other variants;
+    /// the message contains the error returned by the asset loader.
+    #[error(transparent)]
+    Load(Arc<AssetLoadError>),
+
+    /// The file could not be read.
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// The file is neither a valid GLB nor a valid glTF JSON.
+    #[error("Invalid glTF: {0}")]
+    InvalidGltf(String),
+
+    /// The required glTF extension is not found.
+    #[error("Not found {0}")]
+    MissingExtension(&'static str),
+
+    /// The glTF extension could not be deserialized.
+    #[error("Failed to parse {extension}: {source}")]
+    InvalidExtension {
+        extension: &'static str,
+        source: serde_json::Error,
+    },
+
+    /// The spec version of the glTF extension is not supported.
+    #[error("Unsupported spec version of {extension}: {version}")]
+    UnsupportedSpecVersion {
+        extension: &'static str,
+        version: String,
+    },
+
+    /// The node index referenced by the extension is out of range.
+    #[error("The node index {index} of {referrer} is out of range")]
+    InvalidNodeIndex { referrer: String, index: usize },
+
+    /// The VRM has errors that prevent loading, such as out-of-range indices or a missing `hips` bone.
+    ///
+    /// The report contains the errors found by [`VrmValidator`](crate::prelude::VrmValidator).
+    #[error("Invalid VRM: {0}")]
+    Invalid(VrmValidationReport),
+
+    /// `VRMC_materials_mtoon` of the material could not be deserialized.
+    #[error("Failed to parse VRMC_materials_mtoon of {material}: {source}")]
+    InvalidMaterial {
+        material: String,
+        source: serde_json::Error,
+    },
+
+    /// The scene specified in the loader settings is not found.
+    #[error("Not found the scene at index {0}")]
+    MissingScene(usize),
+
+    /// The animation specified in the loader settings is not found.
+    #[error("Not found the animation at index {0}")]
+    MissingAnimation(usize),
+
+    /// The license of the model does not permit the usage required by [`VrmUsagePolicy`](crate::prelude::VrmUsagePolicy).
+    #[error("The license of the model does not permit the usage: {0:?}")]
+    UsageNotPermitted(Vec<VrmUsageViolation>),
+
+    /// The phoneme track of [`LipSyncTrack`](crate::prelude::LipSyncTrack) could not be deserialized.
+    #[error("Failed to parse the lip sync track: {0}")]
+    InvalidLipSyncTrack(serde_json::Error),
+
+    /// A time of [`LipSyncTrack`](crate::prelude::LipSyncTrack) is not finite or negative.
+    #[error("Invalid time of the lip sync track: {0}")]
+    InvalidLipSyncTime(String),
+}
+
+impl From<GltfError> for VrmError {
+    fn from(error: GltfError) -> Self {
+        Self::Gltf(Box::new(error))
+    }
+}
+
 macro_rules! vrm_error {
     ($err:expr) => {
         let _e = $err;
@@ -10,7 +10,10 @@ pub mod vrma;
 #[doc(hidden)]
 pub mod prelude {
     pub use crate::{
-        error::AppResult, system_param::prelude::*, system_set::VrmSystemSets, vrm::prelude::*,
+        error::{AppResult, VrmError},
+        system_param::prelude::*,
+        system_set::VrmSystemSets,
+        vrm::prelude::*,
         vrma::prelude::*,
     };
 }
@@ -18,11 +21,17 @@ pub mod prelude {
 #[doc(hidden)]
 #[cfg(test)]
 pub(crate) mod tests {
-    use bevy::asset::AssetPlugin;
-    use bevy::prelude::ImagePlugin;
+    use bevy::asset::io::memory::{Dir, MemoryAssetReader};
+    use bevy::asset::io::AssetSource;
+    use bevy::asset::{Asset, AssetApp, AssetPlugin, AssetServer, Handle, LoadState};
+    use bevy::gltf::GltfPlugin;
+    use bevy::prelude::{AnimationClip, ImagePlugin, StandardMaterial};
     use bevy::render::camera::CameraPlugin;
+    use bevy::render::mesh::MeshPlugin;
+    use bevy::scene::ScenePlugin;
     use bevy::window::WindowPlugin;
     use bevy::MinimalPlugins;
+    use std::path::Path;
 
     pub type TestResult<T = ()> = Result<T, Box<dyn std::error::Error>>;
 
@@ -44,4 +53,46 @@ pub(crate) mod tests {
         ));
         app
     }
+
+    /// Creates an app whose asset server reads the files from memory,
+    /// with the assets that the glTF loader creates.
+    pub fn test_asset_app(files: &[(&str, &[u8])]) -> bevy::app::App {
+        let dir = Dir::default();
+        for (path, bytes) in files {
+            dir.insert_asset(Path::new(path), bytes.to_vec());
+        }
+        let reader = MemoryAssetReader { root: dir };
+        let mut app = bevy::app::App::new();
+        app.register_asset_source(
+            "memory",
+            AssetSource::build().with_reader(move || Box::new(reader.clone())),
+        )
+        .add_plugins((
+            MinimalPlugins,
+            AssetPlugin::default(),
+            ImagePlugin::default(),
+            ScenePlugin,
+            MeshPlugin,
+            GltfPlugin::default(),
+        ))
+        .init_asset::<StandardMaterial>()
+        .init_asset::<AnimationClip>();
+        app
+    }
+
+    /// Updates the app until the asset is loaded, and returns the error message if it fails.
+    pub fn wait_for_load<A: Asset>(
+        app: &mut bevy::app::App,
+        handle: &Handle<A>,
+    ) -> Result<(), String> {
+        for _ in 0..10_000 {
+            app.update();
+            match app.world().resource::<AssetServer>().load_state(handle) {
+                LoadState::Loaded => return Ok(()),
+                LoadState::Failed(e) => return Err(e.to_string()),
+                _ => std::thread::yield_now(),
+            }
+        }
+        Err("timed out".to_string())
+    }
 }
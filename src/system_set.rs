@@ -5,6 +5,11 @@ pub enum VrmSystemSets {
     /// This is used for retargeting VRMA animations.
     Retarget,
 
+    /// This is used for applying expression weights to morph targets.
+    ///
+    /// Runs after [`VrmSystemSets::LookAt`].
+    Expressions,
+
     /// This is used for look-at functionality.
     LookAt,
 
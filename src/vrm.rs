@@ -1,18 +1,26 @@
 pub(crate) mod expressions;
 pub(crate) mod gltf;
+mod hot_reload;
 pub(crate) mod humanoid_bone;
 mod initialize;
+mod lip_sync;
 mod loader;
 mod look_at;
+mod meta;
 mod mtoon;
 mod spring_bone;
+mod validator;
 
 use crate::macros::marker_component;
 use crate::new_type;
+use crate::system_set::VrmSystemSets;
+use crate::vrm::hot_reload::VrmHotReloadPlugin;
 use crate::vrm::humanoid_bone::VrmHumanoidBonePlugin;
 use crate::vrm::initialize::VrmInitializePlugin;
+use crate::vrm::lip_sync::LipSyncPlugin;
 use crate::vrm::loader::{VrmAsset, VrmLoaderPlugin};
 use crate::vrm::look_at::LookAtPlugin;
+use crate::vrm::meta::VrmMetaPlugin;
 use crate::vrm::spring_bone::VrmSpringBonePlugin;
 use bevy::app::{App, Plugin};
 use bevy::asset::AssetApp;
@@ -23,12 +31,28 @@ use std::path::PathBuf;
 
 pub mod prelude {
     pub use crate::vrm::{
+        expressions::{
+            AutoBlink, ExpressionDefinition, ExpressionMaterialColor, ExpressionNode,
+            ExpressionTextureTransform, ExpressionTransition, SetExpression, VrmExpressionPreset,
+            VrmExpressionRegistry, VrmExpressionTweens, VrmExpressionWeights,
+        },
         gltf::prelude::*,
         humanoid_bone::prelude::*,
-        loader::{VrmAsset, VrmHandle},
-        look_at::LookAt,
+        lip_sync::prelude::*,
+        loader::{VrmAsset, VrmHandle, VrmLoadFailed, VrmLoaderSettings},
+        look_at::{
+            AttentionTarget, GazeBehavior, HeadLookAt, HeadLookAtBone, LookAt, LookAtUpdateMode,
+        },
+        meta::{
+            usage_policy::{
+                VrmUsagePolicy, VrmUsagePolicyAction, VrmUsageViolation, VrmUsageViolations,
+            },
+            vrm_meta::{AvatarPermission, CommercialUsage, CreditNotation, Modification, VrmMeta},
+            VrmMetaAsset, VrmMetaLoaderSettings,
+        },
         mtoon::prelude::*,
-        BoneRestGlobalTransform, BoneRestTransform, Initialized, Vrm, VrmBone, VrmExpression,
+        validator::{VrmValidationIssue, VrmValidationReport, VrmValidator},
+        BoneRestGlobalTransform, BoneRestTransform, Initialized, Vrm, Vrm0, VrmBone, VrmExpression,
         VrmPath, VrmPlugin,
     };
 }
@@ -56,8 +80,19 @@ pub struct Vrm;
 impl Vrm {
     pub const EXPRESSIONS_ROOT: &'static str = "VRMC_vrm.expressions";
     pub const ROOT_BONE: &'static str = "VRMC_vrm.root_bone";
+    /// The name of the entity under the root bone whose rotation is animated by the `lookAt` of VRMA.
+    pub const LOOK_AT_SOURCE: &'static str = "VRMC_vrm.look_at_source";
 }
 
+marker_component!(
+    /// A marker component attached to the entity of VRM converted from VRM 0.x.
+    ///
+    /// The scene of VRM 0.x faces `-Z`, so the loader rotates it by 180 degrees around the Y axis,
+    /// and the model is handled in the same way as VRM 1.0.
+    /// This component is automatically inserted after the [`VrmHandle`](crate::prelude::VrmHandle) is loaded.
+    Vrm0
+);
+
 /// The path to the VRM file.
 /// This component is automatically inserted after the [`VrmHandle`](crate::prelude::VrmHandle) is loaded.
 #[derive(Debug, Reflect, Clone, Component)]
@@ -104,20 +139,29 @@ impl Plugin for VrmPlugin {
     ) {
         app.init_asset::<VrmAsset>().add_plugins((
             VrmLoaderPlugin,
+            VrmMetaPlugin,
             VrmInitializePlugin,
+            VrmHotReloadPlugin,
             VrmSpringBonePlugin,
             VrmHumanoidBonePlugin,
             VrmExpressionPlugin,
+            LipSyncPlugin,
             MtoonMaterialPlugin,
             LookAtPlugin,
         ));
 
         app.register_type::<Vrm>()
+            .register_type::<Vrm0>()
             .register_type::<VrmPath>()
             .register_type::<BoneRestTransform>()
             .register_type::<BoneRestGlobalTransform>()
             .register_type::<VrmBone>()
             .register_type::<VrmExpression>()
-            .register_type::<Initialized>();
+            .register_type::<Initialized>()
+            // The expressions resolve the `lookAt` weights written in the same frame.
+            .configure_sets(
+                PostUpdate,
+                VrmSystemSets::LookAt.before(VrmSystemSets::Expressions),
+            );
     }
 }
@@ -1,30 +1,130 @@
+mod auto_blink;
+mod material;
+mod transition;
+
 use crate::prelude::ChildSearcher;
-use crate::vrm::gltf::extensions::vrmc_vrm::MorphTargetBind;
+use crate::system_set::VrmSystemSets;
+use crate::vrm::expressions::auto_blink::{reset_auto_blink, update_auto_blink};
+use crate::vrm::expressions::material::{bind_materials, MaterialBase};
+use crate::vrm::expressions::transition::{update_expression_crossfades, update_expression_tweens};
+use crate::vrm::gltf::extensions::vrmc_vrm::{
+    ExpressionOverrideType, MaterialColorBind, MaterialColorType, MorphTargetBind,
+    TextureTransformBind, VrmPreset,
+};
 use crate::vrm::gltf::extensions::VrmExtensions;
+use crate::vrm::lip_sync::LipSyncState;
+use crate::vrm::look_at::LookAtExpressionWeights;
 use crate::vrm::{Vrm, VrmExpression};
 use crate::vrma::RetargetSource;
+pub use auto_blink::AutoBlink;
+pub(crate) use auto_blink::{random, seed_random, AutoBlinkState};
 use bevy::animation::{AnimationTarget, AnimationTargetId};
-use bevy::app::Plugin;
-use bevy::asset::{Assets, Handle};
-use bevy::gltf::GltfNode;
+use bevy::app::{Animation, Plugin};
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
+pub(crate) use transition::ExpressionCrossfade;
+pub use transition::{ExpressionTransition, VrmExpressionTweens};
 
+/// A morph target bound to an expression.
 #[derive(Reflect, Debug, Clone)]
-pub(crate) struct ExpressionNode {
+pub struct ExpressionNode {
+    /// The name of the node that has the mesh.
     pub name: Name,
+    /// The index of the morph target.
     pub morph_target_index: usize,
+    /// The weight of the morph target when the weight of the expression is `1.0`.
+    pub weight: f32,
+}
+
+/// A material color bound to an expression.
+#[derive(Reflect, Debug, Clone)]
+pub struct ExpressionMaterialColor {
+    /// The material loaded from glTF, which is converted into [`MToonMaterial`](crate::prelude::MToonMaterial) for each mesh.
+    pub material: AssetId<StandardMaterial>,
+    /// The property to change.
+    pub r#type: MaterialColorType,
+    /// The color when the weight of the expression is `1.0`.
+    pub target: LinearRgba,
+}
+
+/// A texture transform bound to an expression.
+#[derive(Reflect, Debug, Clone)]
+pub struct ExpressionTextureTransform {
+    /// The material loaded from glTF, which is converted into [`MToonMaterial`](crate::prelude::MToonMaterial) for each mesh.
+    pub material: AssetId<StandardMaterial>,
+    /// The UV scale when the weight of the expression is `1.0`.
+    pub scale: Vec2,
+    /// The UV offset when the weight of the expression is `1.0`.
+    pub offset: Vec2,
+}
+
+/// An expression of VRM.
+#[derive(Reflect, Debug, Clone, Default)]
+pub struct ExpressionDefinition {
+    /// The morph targets bound to the expression.
+    pub nodes: Vec<ExpressionNode>,
+    /// The material colors bound to the expression.
+    pub material_colors: Vec<ExpressionMaterialColor>,
+    /// The texture transforms bound to the expression.
+    pub texture_transforms: Vec<ExpressionTextureTransform>,
+    /// If `true`, the weight is snapped to `0.0` or `1.0` at `0.5`.
+    pub is_binary: bool,
+    /// How the expression overrides `blink`, `blinkLeft` and `blinkRight`.
+    pub override_blink: ExpressionOverrideType,
+    /// How the expression overrides `lookUp`, `lookDown`, `lookLeft` and `lookRight`.
+    pub override_look_at: ExpressionOverrideType,
+    /// How the expression overrides `aa`, `ih`, `ou`, `ee` and `oh`.
+    pub override_mouth: ExpressionOverrideType,
+}
+
+impl ExpressionDefinition {
+    fn new(
+        preset: &VrmPreset,
+        node_names: &[Name],
+        materials: &[Handle<StandardMaterial>],
+    ) -> Self {
+        Self {
+            nodes: preset
+                .morph_target_binds
+                .iter()
+                .flatten()
+                .filter_map(|bind| convert_to_node(bind, node_names))
+                .collect(),
+            material_colors: preset
+                .material_color_binds
+                .iter()
+                .flatten()
+                .filter_map(|bind| convert_to_material_color(bind, materials))
+                .collect(),
+            texture_transforms: preset
+                .texture_transform_binds
+                .iter()
+                .flatten()
+                .filter_map(|bind| convert_to_texture_transform(bind, materials))
+                .collect(),
+            is_binary: preset.is_binary,
+            override_blink: preset.override_blink,
+            override_look_at: preset.override_look_at,
+            override_mouth: preset.override_mouth,
+        }
+    }
 }
 
 #[derive(Event)]
 pub(crate) struct RequestInitializeExpressions;
 
+/// The weights of the expressions after [`resolve_expression_weights`], which are bound to morph targets and materials.
+#[derive(Component, Deref, Reflect, Default, Debug, PartialEq)]
+#[reflect(Component)]
+pub(crate) struct ResolvedExpressionWeights(HashMap<VrmExpression, f32>);
+
 #[derive(Reflect)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub(crate) struct BindExpressionNode {
     pub expression_entity: Entity,
     pub index: usize,
+    pub weight: f32,
 }
 
 #[derive(Component, Reflect)]
@@ -33,33 +133,211 @@ pub(crate) struct BindExpressionNode {
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub(crate) struct RetargetExpressionNodes(pub(crate) Vec<BindExpressionNode>);
 
-#[derive(Component, Deref, Reflect)]
-pub(crate) struct VrmExpressionRegistry(pub(crate) HashMap<VrmExpression, Vec<ExpressionNode>>);
+/// Holds the definitions of the expressions.
+///
+/// This is built when VRM is loaded, and inserted into the VRM entity.
+#[derive(Component, Deref, Reflect, Default, Debug, Clone)]
+#[reflect(Component)]
+pub struct VrmExpressionRegistry(pub(crate) HashMap<VrmExpression, ExpressionDefinition>);
 
 impl VrmExpressionRegistry {
-    pub fn new(
+    pub(crate) fn new(
         extensions: &VrmExtensions,
-        node_assets: &Assets<GltfNode>,
-        nodes: &[Handle<GltfNode>],
+        node_names: &[Name],
+        materials: &[Handle<StandardMaterial>],
     ) -> Self {
         let Some(expressions) = extensions.vrmc_vrm.expressions.as_ref() else {
             return Self(HashMap::default());
         };
         Self(
             expressions
-                .preset
                 .iter()
-                .filter_map(|(preset_name, preset)| {
-                    let binds = preset.morph_target_binds.as_ref()?;
-                    let node = binds
-                        .iter()
-                        .filter_map(|bind| convert_to_node(bind, node_assets, nodes))
-                        .collect::<Vec<_>>();
-                    Some((VrmExpression(preset_name.clone()), node))
+                .map(|(preset_name, preset)| {
+                    (
+                        VrmExpression(preset_name.clone()),
+                        ExpressionDefinition::new(preset, node_names, materials),
+                    )
                 })
                 .collect(),
         )
     }
+
+    /// Returns the expressions that the VRM has, including the custom expressions.
+    pub fn expressions(&self) -> impl Iterator<Item = &VrmExpression> {
+        self.0.keys()
+    }
+}
+
+/// The preset expressions defined in the VRM specification.
+///
+/// Please see [`expressions`](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_vrm-1.0/expressions.md) for details.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+pub enum VrmExpressionPreset {
+    Happy,
+    Angry,
+    Sad,
+    Relaxed,
+    Surprised,
+    Aa,
+    Ih,
+    Ou,
+    Ee,
+    Oh,
+    Blink,
+    BlinkLeft,
+    BlinkRight,
+    LookUp,
+    LookDown,
+    LookLeft,
+    LookRight,
+    Neutral,
+}
+
+impl VrmExpressionPreset {
+    /// Returns the name of the preset used in `VRMC_vrm`.
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Happy => "happy",
+            Self::Angry => "angry",
+            Self::Sad => "sad",
+            Self::Relaxed => "relaxed",
+            Self::Surprised => "surprised",
+            Self::Aa => "aa",
+            Self::Ih => "ih",
+            Self::Ou => "ou",
+            Self::Ee => "ee",
+            Self::Oh => "oh",
+            Self::Blink => "blink",
+            Self::BlinkLeft => "blinkLeft",
+            Self::BlinkRight => "blinkRight",
+            Self::LookUp => "lookUp",
+            Self::LookDown => "lookDown",
+            Self::LookLeft => "lookLeft",
+            Self::LookRight => "lookRight",
+            Self::Neutral => "neutral",
+        }
+    }
+}
+
+impl From<VrmExpressionPreset> for VrmExpression {
+    fn from(preset: VrmExpressionPreset) -> Self {
+        Self::from(preset.as_str())
+    }
+}
+
+/// The expression weights set from code.
+///
+/// This component is inserted into the VRM entity when it is spawned.
+/// The weights are added to the weights of the playing VRMA, and the sum is clamped to `0.0..=1.0`.
+/// Expressions that the VRM does not have are ignored;
+/// the available expressions are listed by [`VrmExpressionRegistry::expressions`].
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn smile(mut vrms: Query<&mut VrmExpressionWeights>) {
+///     for mut weights in vrms.iter_mut() {
+///         weights.set(VrmExpressionPreset::Happy, 0.7);
+///         weights.set("myCustomExpression", 1.0);
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+pub struct VrmExpressionWeights(HashMap<VrmExpression, f32>);
+
+impl VrmExpressionWeights {
+    /// Sets the weight of the expression.
+    pub fn set(
+        &mut self,
+        expression: impl Into<VrmExpression>,
+        weight: f32,
+    ) {
+        self.0.insert(expression.into(), weight);
+    }
+
+    /// Returns the weight of the expression, or `0.0` if it is not set.
+    pub fn get(
+        &self,
+        expression: impl Into<VrmExpression>,
+    ) -> f32 {
+        self.0.get(&expression.into()).copied().unwrap_or_default()
+    }
+
+    /// Removes the weight of the expression.
+    pub fn remove(
+        &mut self,
+        expression: impl Into<VrmExpression>,
+    ) -> Option<f32> {
+        self.0.remove(&expression.into())
+    }
+
+    /// Removes all weights.
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Returns the expressions and their weights.
+    pub fn iter(&self) -> impl Iterator<Item = (&VrmExpression, f32)> {
+        self.0
+            .iter()
+            .map(|(expression, weight)| (expression, *weight))
+    }
+}
+
+/// The trigger event to set the weight of an expression through [`VrmExpressionWeights`].
+///
+/// The target of the trigger is the VRM entity.
+/// With [`SetExpression::with_transition`], the weight changes over time through [`VrmExpressionTweens`].
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn smile(
+///     mut commands: Commands,
+///     vrms: Query<Entity, With<Vrm>>,
+/// ) {
+///     for vrm in vrms.iter() {
+///         commands
+///             .entity(vrm)
+///             .trigger(SetExpression::new(VrmExpressionPreset::Happy, 0.7));
+///         commands.entity(vrm).trigger(
+///             SetExpression::new(VrmExpressionPreset::Blink, 1.0)
+///                 .with_transition(ExpressionTransition::default()),
+///         );
+///     }
+/// }
+/// ```
+#[derive(Event, Debug, Clone)]
+pub struct SetExpression {
+    pub expression: VrmExpression,
+    pub weight: f32,
+    /// If `None`, the weight is set immediately.
+    pub transition: Option<ExpressionTransition>,
+}
+
+impl SetExpression {
+    pub fn new(
+        expression: impl Into<VrmExpression>,
+        weight: f32,
+    ) -> Self {
+        Self {
+            expression: expression.into(),
+            weight,
+            transition: None,
+        }
+    }
+
+    /// Changes the weight over the duration of the transition.
+    pub const fn with_transition(
+        mut self,
+        transition: ExpressionTransition,
+    ) -> Self {
+        self.transition = Some(transition);
+        self
+    }
 }
 
 pub(crate) struct VrmExpressionPlugin;
@@ -72,20 +350,66 @@ impl Plugin for VrmExpressionPlugin {
         app.register_type::<BindExpressionNode>()
             .register_type::<RetargetExpressionNodes>()
             .register_type::<VrmExpressionRegistry>()
-            .add_observer(apply_initialize_expressions);
+            .register_type::<VrmExpressionPreset>()
+            .register_type::<VrmExpressionWeights>()
+            .register_type::<ResolvedExpressionWeights>()
+            .register_type::<MaterialBase>()
+            .register_type::<AutoBlink>()
+            .register_type::<AutoBlinkState>()
+            .register_type::<VrmExpressionTweens>()
+            .register_type::<ExpressionCrossfade>()
+            .add_observer(apply_initialize_expressions)
+            .add_observer(apply_set_expression)
+            .add_observer(reset_auto_blink)
+            .add_systems(
+                PostUpdate,
+                (
+                    (
+                        update_auto_blink,
+                        update_expression_tweens,
+                        update_expression_crossfades,
+                    ),
+                    resolve_expressions,
+                    (bind_morph_targets, bind_materials),
+                )
+                    .chain()
+                    .in_set(VrmSystemSets::Expressions)
+                    .after(Animation),
+            );
     }
 }
 
 fn convert_to_node(
     bind: &MorphTargetBind,
-    node_assets: &Assets<GltfNode>,
-    nodes: &[Handle<GltfNode>],
+    node_names: &[Name],
 ) -> Option<ExpressionNode> {
-    let node_handle = nodes.get(bind.node)?;
-    let node = node_assets.get(node_handle)?;
     Some(ExpressionNode {
-        name: Name::new(node.name.clone()),
+        name: node_names.get(bind.node)?.clone(),
         morph_target_index: bind.index,
+        weight: bind.weight,
+    })
+}
+
+fn convert_to_material_color(
+    bind: &MaterialColorBind,
+    materials: &[Handle<StandardMaterial>],
+) -> Option<ExpressionMaterialColor> {
+    let [r, g, b, a] = bind.target_value;
+    Some(ExpressionMaterialColor {
+        material: materials.get(bind.material)?.id(),
+        r#type: bind.r#type,
+        target: LinearRgba::new(r, g, b, a),
+    })
+}
+
+fn convert_to_texture_transform(
+    bind: &TextureTransformBind,
+    materials: &[Handle<StandardMaterial>],
+) -> Option<ExpressionTextureTransform> {
+    Some(ExpressionTextureTransform {
+        material: materials.get(bind.material)?.id(),
+        scale: Vec2::from(bind.scale),
+        offset: Vec2::from(bind.offset),
     })
 }
 
@@ -97,19 +421,27 @@ fn apply_initialize_expressions(
 ) {
     let vrm_entity = trigger.target();
     let expressions_root = commands.spawn(Name::new(Vrm::EXPRESSIONS_ROOT)).id();
-    commands.entity(vrm_entity).add_child(expressions_root);
+    commands
+        .entity(vrm_entity)
+        .insert(ResolvedExpressionWeights::default())
+        .add_child(expressions_root);
 
     let Ok(registry) = expressions.get(vrm_entity) else {
         return;
     };
-    for (expression, nodes) in registry.iter() {
+    for (expression, definition) in registry.iter() {
         let expression_entity = commands
             .spawn((
                 Name::new(expression.to_string()),
                 RetargetSource,
                 Transform::default(),
                 AnimationPlayer::default(),
-                RetargetExpressionNodes(obtain_expression_nodes(vrm_entity, &searcher, nodes)),
+                ExpressionCrossfade::default(),
+                RetargetExpressionNodes(obtain_expression_nodes(
+                    vrm_entity,
+                    &searcher,
+                    &definition.nodes,
+                )),
             ))
             .id();
         commands.entity(expression_entity).insert(AnimationTarget {
@@ -122,6 +454,246 @@ fn apply_initialize_expressions(
     }
 }
 
+fn apply_set_expression(
+    trigger: Trigger<SetExpression>,
+    mut commands: Commands,
+    mut vrms: Query<(
+        Option<&mut VrmExpressionWeights>,
+        Option<&mut VrmExpressionTweens>,
+    )>,
+) {
+    let vrm = trigger.target();
+    let Ok((weights, tweens)) = vrms.get_mut(vrm) else {
+        return;
+    };
+    let expression = trigger.expression.clone();
+    match (trigger.transition, tweens) {
+        (Some(transition), Some(mut tweens)) => {
+            tweens.start(expression, trigger.weight, transition);
+        }
+        (Some(transition), None) => {
+            let mut tweens = VrmExpressionTweens::default();
+            tweens.start(expression, trigger.weight, transition);
+            commands.entity(vrm).insert(tweens);
+        }
+        (None, tweens) => {
+            if let Some(mut tweens) = tweens {
+                tweens.cancel(expression.clone());
+            }
+            if let Some(mut weights) = weights {
+                weights.set(expression, trigger.weight);
+            } else {
+                let mut weights = VrmExpressionWeights::default();
+                weights.set(expression, trigger.weight);
+                commands.entity(vrm).insert(weights);
+            }
+        }
+    }
+}
+
+/// Resolves the expression weights of each VRM.
+///
+/// VRMA animates the x coordinate of the expression entities under [`Vrm::EXPRESSIONS_ROOT`],
+/// which is crossfaded from the previous VRMA by [`ExpressionCrossfade`],
+/// and [`VrmExpressionWeights`], [`AutoBlink`], [`LipSync`](crate::prelude::LipSync) and [`LookAt`](crate::prelude::LookAt)
+/// of the VRM entity are added to it.
+/// The sum is resolved by [`resolve_expression_weights`].
+pub(crate) fn resolve_expressions(
+    mut vrms: Query<(
+        Entity,
+        &VrmExpressionRegistry,
+        Option<Ref<VrmExpressionWeights>>,
+        Option<&AutoBlink>,
+        Option<Ref<AutoBlinkState>>,
+        Option<Ref<LipSyncState>>,
+        Option<Ref<LookAtExpressionWeights>>,
+        &mut ResolvedExpressionWeights,
+    )>,
+    expressions: Query<
+        (Ref<Transform>, Option<Ref<ExpressionCrossfade>>, &Name),
+        With<RetargetExpressionNodes>,
+    >,
+    childrens: Query<&Children>,
+    searcher: ChildSearcher,
+) {
+    for (vrm, registry, user_weights, auto_blink, blink_state, lip_sync, look_at, mut resolved) in
+        vrms.iter_mut()
+    {
+        let Some(expression_entities) = searcher
+            .find_expressions_root(vrm)
+            .and_then(|root| childrens.get(root).ok())
+        else {
+            continue;
+        };
+        let expression_entities = expression_entities
+            .iter()
+            .filter_map(|entity| expressions.get(entity).ok())
+            .collect::<Vec<_>>();
+        if !user_weights.as_ref().is_some_and(Ref::is_changed)
+            && !blink_state.as_ref().is_some_and(Ref::is_changed)
+            && !lip_sync.as_ref().is_some_and(Ref::is_changed)
+            && !look_at.as_ref().is_some_and(Ref::is_changed)
+            && !expression_entities.iter().any(|(tf, crossfade, _)| {
+                tf.is_changed() || crossfade.as_ref().is_some_and(Ref::is_changed)
+            })
+        {
+            continue;
+        }
+        let mut weights = expression_entities
+            .iter()
+            .map(|(tf, crossfade, name)| {
+                let animated_weight = crossfade
+                    .as_ref()
+                    .map(|crossfade| crossfade.blend(tf.translation.x))
+                    .unwrap_or(tf.translation.x);
+                let expression = VrmExpression::from(name.as_str());
+                let user_weight = user_weights
+                    .as_ref()
+                    .map(|weights| weights.get(expression.clone()))
+                    .unwrap_or_default();
+                let blink_weight = auto_blink
+                    .filter(|blink| blink.expressions.contains(&expression))
+                    .and(blink_state.as_ref())
+                    .map(|state| state.weight)
+                    .unwrap_or_default();
+                let viseme_weight = lip_sync
+                    .as_ref()
+                    .and_then(|state| {
+                        state
+                            .expressions()
+                            .into_iter()
+                            .find(|(preset, _)| preset.as_str() == expression.as_str())
+                    })
+                    .map(|(_, weight)| weight)
+                    .unwrap_or_default();
+                let look_at_weight = look_at
+                    .as_ref()
+                    .and_then(|weights| {
+                        weights
+                            .expressions()
+                            .into_iter()
+                            .find(|(preset, _)| preset.as_str() == expression.as_str())
+                    })
+                    .map(|(_, weight)| weight)
+                    .unwrap_or_default();
+                let weight =
+                    (animated_weight + user_weight + blink_weight + viseme_weight + look_at_weight)
+                        .clamp(0.0, 1.0);
+                (expression, weight)
+            })
+            .collect::<Vec<_>>();
+        resolve_expression_weights(registry, &mut weights);
+        resolved.set_if_neq(ResolvedExpressionWeights(weights.into_iter().collect()));
+    }
+}
+
+/// Resolves the morph target weights from the expression weights.
+///
+/// The bound morph targets start from zero, accumulate `bind.weight * expression_weight`,
+/// and are clamped to `0.0..=1.0`, so that several expressions bound to the same morph target compose.
+fn bind_morph_targets(
+    mut morph_weights: Query<&mut MorphWeights>,
+    vrms: Query<(Entity, &ResolvedExpressionWeights), Changed<ResolvedExpressionWeights>>,
+    expressions: Query<(&Name, &RetargetExpressionNodes)>,
+    childrens: Query<&Children>,
+    searcher: ChildSearcher,
+) {
+    for (vrm, resolved) in vrms.iter() {
+        let Some(expression_entities) = searcher
+            .find_expressions_root(vrm)
+            .and_then(|root| childrens.get(root).ok())
+        else {
+            continue;
+        };
+        let mut accumulated = HashMap::<(Entity, usize), f32>::default();
+        for (name, RetargetExpressionNodes(binds)) in expression_entities
+            .iter()
+            .filter_map(|entity| expressions.get(entity).ok())
+        {
+            let expression_weight = resolved
+                .get(&VrmExpression::from(name.as_str()))
+                .copied()
+                .unwrap_or_default();
+            for bind in binds.iter() {
+                *accumulated
+                    .entry((bind.expression_entity, bind.index))
+                    .or_default() += bind.weight * expression_weight;
+            }
+        }
+        for ((entity, index), weight) in accumulated {
+            if let Ok(mut morph_weights) = morph_weights.get_mut(entity) {
+                if let Some(morph_weight) = morph_weights.weights_mut().get_mut(index) {
+                    *morph_weight = weight.clamp(0.0, 1.0);
+                }
+            }
+        }
+    }
+}
+
+/// The preset expression groups that other expressions can override.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum OverrideGroup {
+    Blink,
+    LookAt,
+    Mouth,
+}
+
+impl OverrideGroup {
+    fn of(expression: &str) -> Option<Self> {
+        match expression {
+            "blink" | "blinkLeft" | "blinkRight" => Some(Self::Blink),
+            "lookUp" | "lookDown" | "lookLeft" | "lookRight" => Some(Self::LookAt),
+            "aa" | "ih" | "ou" | "ee" | "oh" => Some(Self::Mouth),
+            _ => None,
+        }
+    }
+}
+
+/// Resolves the weights following `isBinary` and `override*` of the expressions.
+///
+/// Binary expressions are snapped first, and then the weights of the overridden groups are
+/// multiplied by `1 - rate`, where `rate` is the largest rate among the other expressions.
+pub(crate) fn resolve_expression_weights(
+    registry: &VrmExpressionRegistry,
+    weights: &mut [(VrmExpression, f32)],
+) {
+    for (expression, weight) in weights.iter_mut() {
+        if registry.get(expression).is_some_and(|e| e.is_binary) {
+            *weight = if 0.5 < *weight { 1.0 } else { 0.0 };
+        }
+    }
+
+    let mut blink_rate = 0.0_f32;
+    let mut look_at_rate = 0.0_f32;
+    let mut mouth_rate = 0.0_f32;
+    for (expression, weight) in weights.iter() {
+        let Some(definition) = registry.get(expression) else {
+            continue;
+        };
+        // An expression does not override the group it belongs to.
+        let group = OverrideGroup::of(expression);
+        if group != Some(OverrideGroup::Blink) {
+            blink_rate = blink_rate.max(definition.override_blink.rate(*weight));
+        }
+        if group != Some(OverrideGroup::LookAt) {
+            look_at_rate = look_at_rate.max(definition.override_look_at.rate(*weight));
+        }
+        if group != Some(OverrideGroup::Mouth) {
+            mouth_rate = mouth_rate.max(definition.override_mouth.rate(*weight));
+        }
+    }
+
+    for (expression, weight) in weights.iter_mut() {
+        let rate = match OverrideGroup::of(expression) {
+            Some(OverrideGroup::Blink) => blink_rate,
+            Some(OverrideGroup::LookAt) => look_at_rate,
+            Some(OverrideGroup::Mouth) => mouth_rate,
+            None => continue,
+        };
+        *weight *= 1.0 - rate;
+    }
+}
+
 fn obtain_expression_nodes(
     vrm_entity: Entity,
     searcher: &ChildSearcher,
@@ -133,6 +705,7 @@ fn obtain_expression_nodes(
             Some(BindExpressionNode {
                 expression_entity: searcher.find_from_name(vrm_entity, &node.name)?,
                 index: node.morph_target_index,
+                weight: node.weight,
             })
         })
         .collect()
@@ -141,27 +714,40 @@ fn obtain_expression_nodes(
 #[cfg(test)]
 mod tests {
     use crate::prelude::*;
+    use crate::success;
     use crate::tests::{test_app, TestResult};
     use crate::vrm::expressions::{
-        ExpressionNode, RequestInitializeExpressions, VrmExpressionPlugin, VrmExpressionRegistry,
+        resolve_expression_weights, BindExpressionNode, ExpressionDefinition,
+        ExpressionMaterialColor, ExpressionNode, RequestInitializeExpressions,
+        ResolvedExpressionWeights, RetargetExpressionNodes, VrmExpressionPlugin,
+        VrmExpressionRegistry,
     };
+    use crate::vrm::mtoon::MToonMaterialSource;
     use bevy::ecs::system::RunSystemOnce;
+    use bevy::math::Affine2;
     use bevy::prelude::*;
+    use bevy::render::mesh::morph::MorphWeights;
 
     #[test]
     fn test_obtain_expression_nodes() -> TestResult {
         let mut app = test_app();
-        app.add_plugins(VrmExpressionPlugin);
+        app.init_asset::<MToonMaterial>()
+            .init_asset::<AnimationClip>()
+            .add_plugins(VrmExpressionPlugin);
 
         let vrm_entity = app
             .world_mut()
             .spawn((VrmExpressionRegistry(
                 [(
                     VrmExpression::from("happy"),
-                    vec![ExpressionNode {
-                        name: Name::new("Test"),
-                        morph_target_index: 0,
-                    }],
+                    ExpressionDefinition {
+                        nodes: vec![ExpressionNode {
+                            name: Name::new("Test"),
+                            morph_target_index: 0,
+                            weight: 1.0,
+                        }],
+                        ..default()
+                    },
                 )]
                 .into_iter()
                 .collect(),
@@ -186,4 +772,266 @@ mod tests {
             .expect("Expression node not found");
         Ok(())
     }
+
+    #[test]
+    fn add_user_weights_to_animated_weights() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<MToonMaterial>()
+            .init_asset::<AnimationClip>()
+            .add_plugins(VrmExpressionPlugin);
+
+        let mesh = app
+            .world_mut()
+            .spawn(MorphWeights::new(vec![0.0], None)?)
+            .id();
+        let vrm = app
+            .world_mut()
+            .spawn((
+                VrmExpressionRegistry::default(),
+                VrmExpressionWeights::default(),
+                ResolvedExpressionWeights::default(),
+            ))
+            .id();
+        app.world_mut()
+            .spawn((Name::new(Vrm::EXPRESSIONS_ROOT), ChildOf(vrm)))
+            .with_child((
+                Name::new("happy"),
+                Transform::from_xyz(0.2, 0.0, 0.0),
+                RetargetExpressionNodes(vec![BindExpressionNode {
+                    expression_entity: mesh,
+                    index: 0,
+                    weight: 1.0,
+                }]),
+            ));
+        app.world_mut()
+            .commands()
+            .entity(vrm)
+            .trigger(SetExpression::new(VrmExpressionPreset::Happy, 0.5));
+        app.update();
+
+        let weights = app.world().get::<MorphWeights>(mesh).unwrap().weights();
+        assert!((weights[0] - 0.7).abs() < f32::EPSILON);
+
+        app.world_mut()
+            .get_mut::<VrmExpressionWeights>(vrm)
+            .unwrap()
+            .set(VrmExpressionPreset::Happy, 1.0);
+        app.update();
+
+        let weights = app.world().get::<MorphWeights>(mesh).unwrap().weights();
+        assert!((weights[0] - 1.0).abs() < f32::EPSILON);
+        success!()
+    }
+
+    #[test]
+    fn accumulate_morph_weights() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<MToonMaterial>()
+            .init_asset::<AnimationClip>()
+            .add_plugins(VrmExpressionPlugin);
+
+        let mesh = app
+            .world_mut()
+            .spawn(MorphWeights::new(vec![0.0, 0.0], None)?)
+            .id();
+        let vrm = app
+            .world_mut()
+            .spawn((
+                VrmExpressionRegistry::default(),
+                VrmExpressionWeights::default(),
+                ResolvedExpressionWeights::default(),
+            ))
+            .id();
+        let bind = |index: usize, weight: f32| BindExpressionNode {
+            expression_entity: mesh,
+            index,
+            weight,
+        };
+        app.world_mut()
+            .spawn((Name::new(Vrm::EXPRESSIONS_ROOT), ChildOf(vrm)))
+            .with_children(|c| {
+                c.spawn((
+                    Name::new("happy"),
+                    Transform::default(),
+                    RetargetExpressionNodes(vec![bind(0, 0.5), bind(1, 1.0)]),
+                ));
+                c.spawn((
+                    Name::new("relaxed"),
+                    Transform::default(),
+                    RetargetExpressionNodes(vec![bind(0, 0.75)]),
+                ));
+            });
+        let mut weights = app
+            .world_mut()
+            .get_mut::<VrmExpressionWeights>(vrm)
+            .unwrap();
+        weights.set(VrmExpressionPreset::Happy, 1.0);
+        weights.set(VrmExpressionPreset::Relaxed, 1.0);
+        app.update();
+
+        let weights = app.world().get::<MorphWeights>(mesh).unwrap().weights();
+        assert_eq!(weights, [1.0, 1.0]);
+
+        app.world_mut()
+            .get_mut::<VrmExpressionWeights>(vrm)
+            .unwrap()
+            .set(VrmExpressionPreset::Happy, 0.0);
+        app.update();
+
+        let weights = app.world().get::<MorphWeights>(mesh).unwrap().weights();
+        assert_eq!(weights, [0.75, 0.0]);
+        success!()
+    }
+
+    #[test]
+    fn resolve_binary_and_overrides() {
+        let registry = VrmExpressionRegistry(
+            [
+                (
+                    VrmExpression::from("happy"),
+                    ExpressionDefinition {
+                        is_binary: true,
+                        override_blink: ExpressionOverrideType::Block,
+                        override_mouth: ExpressionOverrideType::Blend,
+                        ..default()
+                    },
+                ),
+                (
+                    VrmExpression::from("blink"),
+                    ExpressionDefinition::default(),
+                ),
+                (VrmExpression::from("aa"), ExpressionDefinition::default()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let mut weights = vec![
+            (VrmExpression::from("happy"), 0.6),
+            (VrmExpression::from("blink"), 1.0),
+            (VrmExpression::from("aa"), 0.5),
+        ];
+        resolve_expression_weights(&registry, &mut weights);
+        assert_eq!(weights[0].1, 1.0);
+        assert_eq!(weights[1].1, 0.0);
+        assert_eq!(weights[2].1, 0.0);
+
+        let mut weights = vec![
+            (VrmExpression::from("happy"), 0.4),
+            (VrmExpression::from("blink"), 1.0),
+            (VrmExpression::from("aa"), 0.5),
+        ];
+        resolve_expression_weights(&registry, &mut weights);
+        assert_eq!(weights[0].1, 0.0);
+        assert_eq!(weights[1].1, 1.0);
+        assert_eq!(weights[2].1, 0.5);
+    }
+
+    #[test]
+    fn bind_material_properties() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<MToonMaterial>()
+            .init_asset::<AnimationClip>()
+            .add_plugins(VrmExpressionPlugin);
+
+        let source = AssetId::<StandardMaterial>::default();
+        let material = app
+            .world_mut()
+            .resource_mut::<Assets<MToonMaterial>>()
+            .add(MToonMaterial {
+                base_color: Color::BLACK,
+                ..default()
+            });
+        let registry = VrmExpressionRegistry(
+            [
+                (
+                    VrmExpression::from("blush"),
+                    ExpressionDefinition {
+                        material_colors: vec![ExpressionMaterialColor {
+                            material: source,
+                            r#type: MaterialColorType::Color,
+                            target: LinearRgba::RED,
+                        }],
+                        texture_transforms: vec![ExpressionTextureTransform {
+                            material: source,
+                            scale: Vec2::new(2.0, 1.0),
+                            offset: Vec2::new(0.5, 0.0),
+                        }],
+                        ..default()
+                    },
+                ),
+                (VrmExpression::from("aa"), ExpressionDefinition::default()),
+            ]
+            .into_iter()
+            .collect(),
+        );
+        let vrm = app
+            .world_mut()
+            .spawn((
+                registry,
+                VrmExpressionWeights::default(),
+                ResolvedExpressionWeights::default(),
+            ))
+            .with_child((
+                MeshMaterial3d(material.clone()),
+                MToonMaterialSource(source),
+            ))
+            .id();
+        app.world_mut()
+            .spawn((Name::new(Vrm::EXPRESSIONS_ROOT), ChildOf(vrm)))
+            .with_children(|c| {
+                c.spawn((
+                    Name::new("blush"),
+                    Transform::default(),
+                    RetargetExpressionNodes(Vec::new()),
+                ));
+                c.spawn((
+                    Name::new("aa"),
+                    Transform::default(),
+                    RetargetExpressionNodes(Vec::new()),
+                ));
+            });
+        app.world_mut()
+            .commands()
+            .entity(vrm)
+            .trigger(SetExpression::new("blush", 0.5));
+        app.update();
+
+        let color = app
+            .world()
+            .resource::<Assets<MToonMaterial>>()
+            .get(&material)
+            .unwrap()
+            .base_color
+            .to_linear();
+        assert_eq!(color, LinearRgba::new(0.5, 0.0, 0.0, 1.0));
+
+        let uv_transform = app
+            .world()
+            .resource::<Assets<MToonMaterial>>()
+            .get(&material)
+            .unwrap()
+            .uv_transform;
+        assert_eq!(
+            uv_transform,
+            Affine2::from_scale_angle_translation(Vec2::new(1.5, 1.0), 0.0, Vec2::new(0.25, 0.0))
+        );
+
+        // Changing an expression without material binds does not modify the material.
+        app.update();
+        app.world_mut()
+            .resource_mut::<Events<AssetEvent<MToonMaterial>>>()
+            .clear();
+        app.world_mut()
+            .commands()
+            .entity(vrm)
+            .trigger(SetExpression::new("aa", 1.0));
+        app.update();
+        app.update();
+        let events = app.world().resource::<Events<AssetEvent<MToonMaterial>>>();
+        assert!(!events
+            .get_cursor()
+            .read(events)
+            .any(|event| event.is_modified(&material)));
+        success!()
+    }
 }
@@ -0,0 +1,391 @@
+//! This module drives the blink expressions automatically with [`AutoBlink`].
+
+use crate::prelude::ChildSearcher;
+use crate::vrm::VrmExpression;
+use crate::vrma::animation::is_animated_by_vrma;
+use crate::vrma::{VrmAnimationClipHandle, VrmAnimationNodeIndex};
+use bevy::animation::AnimationTarget;
+use bevy::prelude::*;
+
+/// Blinks the VRM automatically.
+///
+/// The weight is added to the expressions in [`AutoBlink::expressions`], so it respects `overrideBlink` of the other expressions.
+/// While the playing VRMA animates any of the expressions, blinking is paused.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn spawn(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     commands.spawn((
+///         VrmHandle(asset_server.load("vrm/AliciaSolid.vrm")),
+///         AutoBlink {
+///             double_blink_probability: 0.2,
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component, Default)]
+#[require(AutoBlinkState)]
+pub struct AutoBlink {
+    /// The expressions to drive.
+    ///
+    /// Defaults to `blink`; specify `blinkLeft` and `blinkRight` to drive each eye.
+    pub expressions: Vec<VrmExpression>,
+    /// The minimum seconds between blinks.
+    pub min_interval: f32,
+    /// The maximum seconds between blinks.
+    pub max_interval: f32,
+    /// The seconds to close the eyes.
+    pub close_duration: f32,
+    /// The seconds to keep the eyes closed.
+    pub closed_duration: f32,
+    /// The seconds to open the eyes.
+    pub open_duration: f32,
+    /// The curve of closing and opening the eyes.
+    pub ease: EaseFunction,
+    /// The probability of blinking twice in a row, in the range of `0.0` to `1.0`.
+    pub double_blink_probability: f32,
+    /// The seconds between the blinks of a double blink.
+    pub double_blink_interval: f32,
+}
+
+impl Default for AutoBlink {
+    fn default() -> Self {
+        Self {
+            expressions: vec![VrmExpression::from("blink")],
+            min_interval: 2.0,
+            max_interval: 6.0,
+            close_duration: 0.06,
+            closed_duration: 0.04,
+            open_duration: 0.12,
+            ease: EaseFunction::SineInOut,
+            double_blink_probability: 0.1,
+            double_blink_interval: 0.1,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Copy, Clone, PartialEq)]
+enum BlinkPhase {
+    Waiting,
+    Closing,
+    Closed,
+    Opening,
+}
+
+impl BlinkPhase {
+    const COUNT: usize = 4;
+}
+
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub(crate) struct AutoBlinkState {
+    phase: BlinkPhase,
+    /// The remaining seconds of the current phase.
+    remaining: f32,
+    double_blink: bool,
+    rng: u64,
+    /// The weight added to the blink expressions.
+    pub(crate) weight: f32,
+}
+
+impl Default for AutoBlinkState {
+    fn default() -> Self {
+        Self {
+            phase: BlinkPhase::Waiting,
+            remaining: 1.0,
+            double_blink: false,
+            rng: 0,
+            weight: 0.0,
+        }
+    }
+}
+
+/// Returns a pseudo random number in the range of `0.0` to `1.0` with xorshift64.
+///
+/// `rng` must not be zero.
+pub(crate) fn random(rng: &mut u64) -> f32 {
+    *rng ^= *rng << 13;
+    *rng ^= *rng >> 7;
+    *rng ^= *rng << 17;
+    (*rng >> 40) as f32 / (1u64 << 24) as f32
+}
+
+/// Returns the state of [`random`] seeded with `seed`.
+///
+/// Entities spawned together have close bits, so they are scattered before seeding.
+pub(crate) const fn seed_random(seed: u64) -> u64 {
+    seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1
+}
+
+impl AutoBlinkState {
+    fn random(&mut self) -> f32 {
+        random(&mut self.rng)
+    }
+
+    /// Seeds the random numbers and draws the first interval,
+    /// so that the VRMs spawned together do not blink in sync.
+    fn seed(
+        &mut self,
+        seed: u64,
+        blink: &AutoBlink,
+    ) {
+        self.rng = seed_random(seed);
+        self.reset(blink);
+    }
+
+    /// Opens the eyes and waits for a random interval.
+    fn reset(
+        &mut self,
+        blink: &AutoBlink,
+    ) {
+        self.phase = BlinkPhase::Waiting;
+        self.double_blink = false;
+        self.remaining = self.random_interval(blink);
+    }
+
+    fn random_interval(
+        &mut self,
+        blink: &AutoBlink,
+    ) -> f32 {
+        blink.min_interval + (blink.max_interval - blink.min_interval).max(0.0) * self.random()
+    }
+
+    /// Starts closing the eyes in the next update unless they are already blinking.
+    pub(crate) fn request_blink(&mut self) {
+        if self.phase == BlinkPhase::Waiting {
+            self.remaining = 0.0;
+        }
+    }
+
+    fn advance(
+        &mut self,
+        blink: &AutoBlink,
+        delta: f32,
+    ) {
+        self.remaining -= delta;
+        // A frame goes through the phases at most once, so that zero durations do not spin the loop.
+        for _ in 0..BlinkPhase::COUNT {
+            if 0.0 < self.remaining {
+                return;
+            }
+            let (phase, duration) = match self.phase {
+                BlinkPhase::Waiting => (BlinkPhase::Closing, blink.close_duration),
+                BlinkPhase::Closing => (BlinkPhase::Closed, blink.closed_duration),
+                BlinkPhase::Closed => (BlinkPhase::Opening, blink.open_duration),
+                BlinkPhase::Opening if self.double_blink => {
+                    self.double_blink = false;
+                    (BlinkPhase::Waiting, blink.double_blink_interval)
+                }
+                BlinkPhase::Opening => {
+                    self.double_blink = self.random() < blink.double_blink_probability;
+                    (BlinkPhase::Waiting, self.random_interval(blink))
+                }
+            };
+            self.phase = phase;
+            self.remaining += duration;
+        }
+        self.remaining = self.remaining.max(0.0);
+    }
+
+    fn obtain_weight(
+        &self,
+        blink: &AutoBlink,
+    ) -> f32 {
+        let progress = |duration: f32| 1.0 - (self.remaining / duration.max(f32::EPSILON));
+        match self.phase {
+            BlinkPhase::Waiting => 0.0,
+            BlinkPhase::Closing => blink.ease.sample_clamped(progress(blink.close_duration)),
+            BlinkPhase::Closed => 1.0,
+            BlinkPhase::Opening => 1.0 - blink.ease.sample_clamped(progress(blink.open_duration)),
+        }
+    }
+}
+
+pub(super) fn update_auto_blink(
+    mut vrms: Query<(Entity, &AutoBlink, &mut AutoBlinkState)>,
+    targets: Query<&AnimationTarget>,
+    players: Query<&AnimationPlayer>,
+    vrmas: Query<(&VrmAnimationNodeIndex, &VrmAnimationClipHandle, &ChildOf)>,
+    clips: Res<Assets<AnimationClip>>,
+    searcher: ChildSearcher,
+    time: Res<Time>,
+) {
+    for (vrm, blink, mut state) in vrms.iter_mut() {
+        let animated = searcher.find_expressions_root(vrm).is_some_and(|root| {
+            blink.expressions.iter().any(|expression| {
+                searcher
+                    .find_from_name(root, expression)
+                    .and_then(|entity| targets.get(entity).ok())
+                    .is_some_and(|target| {
+                        is_animated_by_vrma(vrm, target, &players, &vrmas, &clips)
+                    })
+            })
+        });
+        // The state is marked as changed only when the weight changes.
+        let inner = state.bypass_change_detection();
+        if inner.rng == 0 {
+            inner.seed(vrm.to_bits(), blink);
+        }
+        let weight = if animated {
+            // Blinking resumes after a random interval when the animation stops.
+            inner.reset(blink);
+            0.0
+        } else {
+            inner.advance(blink, time.delta_secs());
+            inner.obtain_weight(blink)
+        };
+        if state.weight != weight {
+            state.weight = weight;
+        }
+    }
+}
+
+/// Opens the eyes when [`AutoBlink`] is removed, so that they do not stay closed mid-blink.
+pub(super) fn reset_auto_blink(
+    trigger: Trigger<OnRemove, AutoBlink>,
+    mut states: Query<&mut AutoBlinkState>,
+) {
+    if let Ok(mut state) = states.get_mut(trigger.target()) {
+        state.phase = BlinkPhase::Waiting;
+        state.weight = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::success;
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::expressions::auto_blink::{AutoBlink, AutoBlinkState, BlinkPhase};
+    use crate::vrm::expressions::{
+        BindExpressionNode, ResolvedExpressionWeights, RetargetExpressionNodes, VrmExpressionPlugin,
+    };
+    use bevy::prelude::*;
+    use bevy::render::mesh::morph::MorphWeights;
+
+    #[test]
+    fn blink_cycle() {
+        let blink = AutoBlink {
+            double_blink_probability: 0.0,
+            ..Default::default()
+        };
+        let mut state = AutoBlinkState {
+            rng: 1,
+            ..Default::default()
+        };
+        state.advance(&blink, 1.0 + blink.close_duration / 2.0);
+        assert_eq!(state.phase, BlinkPhase::Closing);
+        let weight = state.obtain_weight(&blink);
+        assert!(0.0 < weight && weight < 1.0);
+
+        state.advance(
+            &blink,
+            blink.close_duration / 2.0 + blink.closed_duration / 2.0,
+        );
+        assert_eq!(state.phase, BlinkPhase::Closed);
+        assert_eq!(state.obtain_weight(&blink), 1.0);
+
+        state.advance(
+            &blink,
+            blink.closed_duration / 2.0 + blink.open_duration / 2.0,
+        );
+        assert_eq!(state.phase, BlinkPhase::Opening);
+
+        state.advance(&blink, blink.open_duration);
+        assert_eq!(state.phase, BlinkPhase::Waiting);
+        assert_eq!(state.obtain_weight(&blink), 0.0);
+        assert!(state.remaining <= blink.max_interval);
+    }
+
+    #[test]
+    fn scatter_first_intervals() {
+        let blink = AutoBlink::default();
+        let intervals = (0..4u64)
+            .map(|seed| {
+                let mut state = AutoBlinkState::default();
+                state.seed(seed, &blink);
+                state.remaining
+            })
+            .collect::<Vec<_>>();
+        for (i, interval) in intervals.iter().enumerate() {
+            assert!((blink.min_interval..=blink.max_interval).contains(interval));
+            assert!(intervals[..i].iter().all(|other| other != interval));
+        }
+    }
+
+    #[test]
+    fn advance_zero_durations() {
+        let blink = AutoBlink {
+            min_interval: 0.0,
+            max_interval: 0.0,
+            close_duration: 0.0,
+            closed_duration: 0.0,
+            open_duration: 0.0,
+            ..Default::default()
+        };
+        let mut state = AutoBlinkState {
+            rng: 1,
+            ..Default::default()
+        };
+        state.advance(&blink, 1.0e6);
+        assert_eq!(state.phase, BlinkPhase::Waiting);
+        assert_eq!(state.remaining, 0.0);
+    }
+
+    #[test]
+    fn open_eyes_on_remove() -> TestResult {
+        let mut app = test_app();
+        app.init_asset::<MToonMaterial>()
+            .init_asset::<AnimationClip>()
+            .add_plugins(VrmExpressionPlugin);
+
+        let mesh = app
+            .world_mut()
+            .spawn(MorphWeights::new(vec![0.0], None)?)
+            .id();
+        let vrm = app
+            .world_mut()
+            .spawn((
+                VrmExpressionRegistry::default(),
+                ResolvedExpressionWeights::default(),
+                AutoBlink {
+                    closed_duration: 1.0e6,
+                    ..default()
+                },
+                AutoBlinkState {
+                    phase: BlinkPhase::Closed,
+                    remaining: 1.0e6,
+                    rng: 1,
+                    ..default()
+                },
+            ))
+            .id();
+        app.world_mut()
+            .spawn((Name::new(Vrm::EXPRESSIONS_ROOT), ChildOf(vrm)))
+            .with_child((
+                Name::new("blink"),
+                Transform::default(),
+                RetargetExpressionNodes(vec![BindExpressionNode {
+                    expression_entity: mesh,
+                    index: 0,
+                    weight: 1.0,
+                }]),
+            ));
+        app.update();
+        let weights = app.world().get::<MorphWeights>(mesh).unwrap().weights();
+        assert_eq!(weights, [1.0]);
+
+        app.world_mut().entity_mut(vrm).remove::<AutoBlink>();
+        app.update();
+        let weights = app.world().get::<MorphWeights>(mesh).unwrap().weights();
+        assert_eq!(weights, [0.0]);
+        success!()
+    }
+}
@@ -0,0 +1,177 @@
+//! This module applies `materialColorBinds` and `textureTransformBinds` of expressions to [`MToonMaterial`].
+//!
+//! Each mesh has its own [`MToonMaterial`] converted from the glTF material,
+//! so the colors of one VRM do not affect the other instances.
+
+use crate::prelude::MToonMaterial;
+use crate::vrm::expressions::{ResolvedExpressionWeights, VrmExpressionRegistry};
+use crate::vrm::gltf::extensions::vrmc_vrm::MaterialColorType;
+use crate::vrm::mtoon::MToonMaterialSource;
+use bevy::math::Affine2;
+use bevy::platform::collections::{HashMap, HashSet};
+use bevy::prelude::*;
+
+/// The properties of the material before the expressions are applied.
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+pub(crate) struct MaterialBase {
+    colors: HashMap<MaterialColorType, LinearRgba>,
+    /// The transform from `KHR_texture_transform`.
+    uv_transform: Affine2,
+}
+
+impl MaterialBase {
+    fn new(material: &MToonMaterial) -> Self {
+        Self {
+            colors: MaterialColorType::ALL
+                .into_iter()
+                .map(|ty| (ty, ty.get(material)))
+                .collect(),
+            uv_transform: material.uv_transform,
+        }
+    }
+}
+
+impl MaterialColorType {
+    const ALL: [Self; 6] = [
+        Self::Color,
+        Self::EmissionColor,
+        Self::ShadeColor,
+        Self::MatcapColor,
+        Self::RimColor,
+        Self::OutlineColor,
+    ];
+
+    fn get(
+        self,
+        material: &MToonMaterial,
+    ) -> LinearRgba {
+        match self {
+            Self::Color => material.base_color.to_linear(),
+            Self::EmissionColor => material.emissive,
+            Self::ShadeColor => material.shade.color,
+            Self::MatcapColor => material.rim_lighting.mat_cap_color,
+            Self::RimColor => material.rim_lighting.color,
+            Self::OutlineColor => material.outline.color,
+        }
+    }
+
+    fn set(
+        self,
+        material: &mut MToonMaterial,
+        color: LinearRgba,
+    ) {
+        match self {
+            Self::Color => material.base_color = Color::LinearRgba(color),
+            Self::EmissionColor => material.emissive = color,
+            Self::ShadeColor => material.shade.color = color,
+            Self::MatcapColor => material.rim_lighting.mat_cap_color = color,
+            Self::RimColor => material.rim_lighting.color = color,
+            Self::OutlineColor => material.outline.color = color,
+        }
+    }
+}
+
+/// Applies the material binds of the expressions to the materials of the meshes.
+///
+/// The colors are lerped from their base colors toward the target colors by the expression weights.
+/// The texture transforms are blended as `scale = 1 + Σ (scale - 1) * weight` and `offset = Σ offset * weight`,
+/// and applied on top of the base transform.
+///
+/// Since taking a material mutably marks it modified and uploads it to the GPU again,
+/// the material is only written when the blended properties differ from the current ones.
+pub(super) fn bind_materials(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<MToonMaterial>>,
+    vrms: Query<
+        (Entity, &VrmExpressionRegistry, &ResolvedExpressionWeights),
+        Changed<ResolvedExpressionWeights>,
+    >,
+    meshes: Query<(
+        Entity,
+        &MeshMaterial3d<MToonMaterial>,
+        &MToonMaterialSource,
+        Option<&MaterialBase>,
+    )>,
+    childrens: Query<&Children>,
+) {
+    for (vrm, registry, resolved) in vrms.iter() {
+        let bound_materials = registry
+            .values()
+            .flat_map(|definition| {
+                definition
+                    .material_colors
+                    .iter()
+                    .map(|bind| bind.material)
+                    .chain(
+                        definition
+                            .texture_transforms
+                            .iter()
+                            .map(|bind| bind.material),
+                    )
+            })
+            .collect::<HashSet<_>>();
+        if bound_materials.is_empty() {
+            continue;
+        }
+        for (entity, handle, source, base) in childrens
+            .iter_descendants(vrm)
+            .filter_map(|entity| meshes.get(entity).ok())
+        {
+            if !bound_materials.contains(&source.0) {
+                continue;
+            }
+            let Some(material) = materials.get(handle) else {
+                continue;
+            };
+            let base = match base {
+                Some(base) => base.clone(),
+                None => {
+                    let base = MaterialBase::new(material);
+                    commands.entity(entity).insert(base.clone());
+                    base
+                }
+            };
+            let mut colors = base.colors.clone();
+            let mut scale = Vec2::ONE;
+            let mut offset = Vec2::ZERO;
+            for (expression, definition) in registry.iter() {
+                let weight = resolved.get(expression).copied().unwrap_or_default();
+                for bind in definition
+                    .material_colors
+                    .iter()
+                    .filter(|bind| bind.material == source.0)
+                {
+                    let Some(base_color) = base.colors.get(&bind.r#type) else {
+                        continue;
+                    };
+                    if let Some(color) = colors.get_mut(&bind.r#type) {
+                        *color += (bind.target - *base_color) * weight;
+                    }
+                }
+                for bind in definition
+                    .texture_transforms
+                    .iter()
+                    .filter(|bind| bind.material == source.0)
+                {
+                    scale += (bind.scale - Vec2::ONE) * weight;
+                    offset += bind.offset * weight;
+                }
+            }
+            let uv_transform =
+                Affine2::from_scale_angle_translation(scale, 0.0, offset) * base.uv_transform;
+            if material.uv_transform == uv_transform
+                && colors.iter().all(|(ty, color)| ty.get(material) == *color)
+            {
+                continue;
+            }
+            let Some(material) = materials.get_mut(handle) else {
+                continue;
+            };
+            for (ty, color) in colors {
+                ty.set(material, color);
+            }
+            material.uv_transform = uv_transform;
+        }
+    }
+}
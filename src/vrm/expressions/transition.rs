@@ -0,0 +1,236 @@
+//! This module moves the expression weights smoothly over time.
+//!
+//! [`VrmExpressionTweens`] animates [`VrmExpressionWeights`] toward target weights,
+//! and [`ExpressionCrossfade`] blends the weights animated by the previous VRMA into the next one.
+
+use crate::vrm::expressions::VrmExpressionWeights;
+use crate::vrm::VrmExpression;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use std::time::Duration;
+
+/// The duration and the curve of changing an expression weight.
+#[derive(Reflect, Debug, Copy, Clone, PartialEq)]
+pub struct ExpressionTransition {
+    pub duration: Duration,
+    pub ease: EaseFunction,
+}
+
+impl ExpressionTransition {
+    pub const fn new(
+        duration: Duration,
+        ease: EaseFunction,
+    ) -> Self {
+        Self { duration, ease }
+    }
+}
+
+impl Default for ExpressionTransition {
+    fn default() -> Self {
+        Self {
+            duration: Duration::from_millis(200),
+            ease: EaseFunction::SineInOut,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Clone, PartialEq)]
+struct ExpressionTween {
+    /// The weight when the tween started, captured on the first update.
+    from: Option<f32>,
+    to: f32,
+    elapsed: Duration,
+    transition: ExpressionTransition,
+}
+
+/// Moves the weights of [`VrmExpressionWeights`] toward the target weights.
+///
+/// A tween started while another one of the same expression is running continues from the current weight.
+/// Setting the weight in [`VrmExpressionWeights`] directly while tweening is overwritten by the tween.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+/// use std::time::Duration;
+///
+/// fn smile(mut vrms: Query<&mut VrmExpressionTweens>) {
+///     for mut tweens in vrms.iter_mut() {
+///         tweens.start(
+///             VrmExpressionPreset::Happy,
+///             1.0,
+///             ExpressionTransition::new(Duration::from_millis(500), EaseFunction::CubicOut),
+///         );
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Default, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+#[require(VrmExpressionWeights)]
+pub struct VrmExpressionTweens(HashMap<VrmExpression, ExpressionTween>);
+
+impl VrmExpressionTweens {
+    /// Starts moving the weight of the expression to `weight`.
+    pub fn start(
+        &mut self,
+        expression: impl Into<VrmExpression>,
+        weight: f32,
+        transition: ExpressionTransition,
+    ) {
+        self.0.insert(
+            expression.into(),
+            ExpressionTween {
+                from: None,
+                to: weight,
+                elapsed: Duration::ZERO,
+                transition,
+            },
+        );
+    }
+
+    /// Stops the tween of the expression, keeping the current weight.
+    pub fn cancel(
+        &mut self,
+        expression: impl Into<VrmExpression>,
+    ) -> bool {
+        self.0.remove(&expression.into()).is_some()
+    }
+
+    /// Returns `true` if the expression is tweening.
+    pub fn is_tweening(
+        &self,
+        expression: impl Into<VrmExpression>,
+    ) -> bool {
+        self.0.contains_key(&expression.into())
+    }
+
+    fn advance(
+        &mut self,
+        weights: &mut VrmExpressionWeights,
+        delta: Duration,
+    ) {
+        self.0.retain(|expression, tween| {
+            let from = *tween
+                .from
+                .get_or_insert_with(|| weights.get(expression.clone()));
+            tween.elapsed += delta;
+            let duration = tween.transition.duration;
+            let progress = if duration.is_zero() {
+                1.0
+            } else {
+                tween.elapsed.as_secs_f32() / duration.as_secs_f32()
+            };
+            let t = tween.transition.ease.sample_clamped(progress);
+            weights.set(expression.clone(), from.lerp(tween.to, t));
+            progress < 1.0
+        });
+    }
+}
+
+/// Blends the weight of an expression from the one when the previous VRMA was switched.
+///
+/// The expression players restart for the next VRMA, so the weight animated by the previous one
+/// is kept in `from` and faded out over the transition duration of [`PlayVrma`](crate::prelude::PlayVrma).
+#[derive(Component, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub(crate) struct ExpressionCrossfade {
+    from: f32,
+    elapsed: Duration,
+    duration: Duration,
+}
+
+impl ExpressionCrossfade {
+    pub(crate) fn start(
+        &mut self,
+        animated: f32,
+        duration: Duration,
+    ) {
+        *self = Self {
+            from: self.blend(animated),
+            elapsed: Duration::ZERO,
+            duration,
+        };
+    }
+
+    /// Returns the weight blended from `from` into the weight animated by the current VRMA.
+    pub(crate) fn blend(
+        &self,
+        animated: f32,
+    ) -> f32 {
+        if self.is_finished() {
+            return animated;
+        }
+        let t = self.elapsed.as_secs_f32() / self.duration.as_secs_f32();
+        self.from.lerp(animated, t)
+    }
+
+    fn is_finished(&self) -> bool {
+        self.duration <= self.elapsed
+    }
+}
+
+pub(super) fn update_expression_tweens(
+    mut vrms: Query<(&mut VrmExpressionTweens, &mut VrmExpressionWeights)>,
+    time: Res<Time>,
+) {
+    for (mut tweens, mut weights) in vrms.iter_mut() {
+        if tweens.0.is_empty() {
+            continue;
+        }
+        tweens.advance(&mut weights, time.delta());
+    }
+}
+
+pub(super) fn update_expression_crossfades(
+    mut crossfades: Query<&mut ExpressionCrossfade>,
+    time: Res<Time>,
+) {
+    for mut crossfade in crossfades.iter_mut() {
+        if crossfade.is_finished() {
+            continue;
+        }
+        crossfade.elapsed = (crossfade.elapsed + time.delta()).min(crossfade.duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::expressions::transition::{
+        ExpressionCrossfade, ExpressionTransition, VrmExpressionTweens,
+    };
+    use crate::vrm::expressions::VrmExpressionWeights;
+    use bevy::prelude::EaseFunction;
+    use std::time::Duration;
+
+    #[test]
+    fn tween_weights() {
+        let mut weights = VrmExpressionWeights::default();
+        weights.set("happy", 0.2);
+        let mut tweens = VrmExpressionTweens::default();
+        tweens.start(
+            "happy",
+            1.0,
+            ExpressionTransition::new(Duration::from_millis(400), EaseFunction::Linear),
+        );
+
+        tweens.advance(&mut weights, Duration::from_millis(200));
+        assert!((weights.get("happy") - 0.6).abs() < 1e-4);
+        assert!(tweens.is_tweening("happy"));
+
+        tweens.advance(&mut weights, Duration::from_millis(300));
+        assert_eq!(weights.get("happy"), 1.0);
+        assert!(!tweens.is_tweening("happy"));
+    }
+
+    #[test]
+    fn crossfade_weights() {
+        let mut crossfade = ExpressionCrossfade::default();
+        assert_eq!(crossfade.blend(0.3), 0.3);
+
+        crossfade.start(0.8, Duration::from_millis(300));
+        assert_eq!(crossfade.blend(0.0), 0.8);
+        crossfade.elapsed = Duration::from_millis(150);
+        assert!((crossfade.blend(0.2) - 0.5).abs() < 1e-4);
+        crossfade.elapsed = Duration::from_millis(300);
+        assert_eq!(crossfade.blend(0.2), 0.2);
+    }
+}
@@ -1,9 +1,32 @@
 pub mod extensions;
+pub(crate) mod glb;
 pub mod materials;
 
+use bevy::gltf::Gltf;
+use bevy::prelude::Name;
+
 pub mod prelude {
     pub use crate::vrm::gltf::{
         extensions::{vrmc_spring_bone::*, vrmc_vrm::*, VrmExtensions, VrmNode},
         materials::*,
     };
 }
+
+/// Returns the names of all nodes in the order of the node indices.
+///
+/// The names are the same as [`Name`] of the entities spawned from the glTF scene.
+pub(crate) fn node_names(gltf: &Gltf) -> Vec<Name> {
+    let Some(source) = gltf.source.as_ref() else {
+        return Vec::new();
+    };
+    source
+        .nodes()
+        .map(|node| {
+            Name::new(
+                node.name()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| format!("GltfNode{}", node.index())),
+            )
+        })
+        .collect()
+}
@@ -1,14 +1,16 @@
+pub(crate) mod vrm0;
 pub mod vrmc_spring_bone;
 pub mod vrmc_vrm;
 
-use crate::error::AppResult;
+use crate::error::{vrm_error, VrmError};
+use crate::vrm::gltf::extensions::vrm0::{GltfDocument, Vrm0};
 use crate::vrm::gltf::extensions::vrmc_spring_bone::VRMCSpringBone;
 use crate::vrm::gltf::extensions::vrmc_vrm::VrmcVrm;
-use anyhow::Context;
+use crate::vrm::validator::{VrmValidationReport, VrmValidator};
 use bevy::gltf::Gltf;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct VrmExtensions {
     #[serde(rename = "VRMC_vrm")]
     pub vrmc_vrm: VrmcVrm,
@@ -18,18 +20,85 @@ pub struct VrmExtensions {
 }
 
 impl VrmExtensions {
-    pub fn new(json: &serde_json::map::Map<String, serde_json::Value>) -> AppResult<Self> {
+    /// Deserializes the VRM 1.0 extensions.
+    ///
+    /// The extensions are not validated; use [`VrmValidator`](crate::prelude::VrmValidator) to check them.
+    pub fn new(json: &serde_json::map::Map<String, serde_json::Value>) -> Result<Self, VrmError> {
+        let vrmc_vrm: VrmcVrm = parse_extension(json, VRMC_VRM)?;
+        // The spring bones are optional, so the model is loaded without them if they are malformed.
+        let vrmc_spring_bone = match json.get(VRMC_SPRING_BONE) {
+            Some(_) => match parse_extension(json, VRMC_SPRING_BONE) {
+                Ok(spring_bone) => Some(spring_bone),
+                Err(e) => {
+                    vrm_error!(e);
+                    None
+                }
+            },
+            None => None,
+        };
         Ok(Self {
-            vrmc_vrm: serde_json::from_value(obtain_vrmc_vrm(json)?)?,
-            vrmc_spring_bone: obtain_vrmc_springs(json)
-                .ok()
-                .and_then(|v| serde_json::from_value(v).ok()),
+            vrmc_vrm,
+            vrmc_spring_bone,
         })
     }
 
     /// Creates a new [`VrmExtensions`] from the glTF asset.
-    pub fn from_gltf(gltf: &Gltf) -> AppResult<Self> {
-        Self::new(obtain_extensions(gltf)?)
+    ///
+    /// If the asset only has the VRM 0.x `VRM` extension, it is converted into the VRM 1.0 structures.
+    /// Returns [`VrmError::Invalid`] if [`VrmValidator`](crate::prelude::VrmValidator) finds a [fatal](crate::prelude::VrmValidationIssue::is_fatal) error.
+    pub fn from_gltf(gltf: &Gltf) -> Result<Self, VrmError> {
+        let json = obtain_extensions(gltf).ok_or(VrmError::MissingExtension(VRMC_VRM))?;
+        let vrm0 = parse_vrm0(json)?;
+        let (extensions, _) = Self::from_document(json, vrm0.as_ref(), &GltfDocument::from(gltf))?;
+        Ok(extensions)
+    }
+
+    /// Creates a new [`VrmExtensions`] from the root extensions and the document they belong to,
+    /// and validates them.
+    ///
+    /// If `vrm0` is given, which is parsed by [`parse_vrm0`], it is converted instead of the VRM 1.0 extensions.
+    /// Only the fatal errors fail; the other findings are returned in the report.
+    pub(crate) fn from_document(
+        json: &serde_json::map::Map<String, serde_json::Value>,
+        vrm0: Option<&Vrm0>,
+        document: &GltfDocument,
+    ) -> Result<(Self, VrmValidationReport), VrmError> {
+        let extensions = match vrm0 {
+            Some(vrm0) => Self::from_vrm0(vrm0, document),
+            None => Self::new(json)?,
+        };
+        let report = VrmValidator::validate_document(&extensions, document);
+        if report.is_loadable() {
+            Ok((extensions, report))
+        } else {
+            Err(VrmError::Invalid(report))
+        }
+    }
+
+    /// Creates a new [`VrmExtensions`] from the root extensions without validation.
+    pub(crate) fn parse_document(
+        json: &serde_json::map::Map<String, serde_json::Value>,
+        document: &GltfDocument,
+    ) -> Result<Self, VrmError> {
+        match parse_vrm0(json)? {
+            Some(vrm0) => Ok(Self::from_vrm0(&vrm0, document)),
+            None => Self::new(json),
+        }
+    }
+
+    pub(crate) fn from_vrm0(
+        vrm0: &Vrm0,
+        document: &GltfDocument,
+    ) -> Self {
+        Self {
+            vrmc_vrm: vrm0.to_vrmc_vrm(document),
+            vrmc_spring_bone: vrm0.to_vrmc_spring_bone(document),
+        }
+    }
+
+    /// Returns `true` if the extensions have been converted from VRM 0.x.
+    pub fn is_vrm0(&self) -> bool {
+        self.vrmc_vrm.spec_version.starts_with("0.")
     }
 
     /// Gets the name of the VRM avatar.
@@ -47,30 +116,111 @@ pub struct VrmNode {
     pub node: usize,
 }
 
+pub(crate) const VRMC_VRM: &str = "VRMC_vrm";
+pub(crate) const VRMC_SPRING_BONE: &str = "VRMC_springBone";
+pub(crate) const VRM0: &str = "VRM";
+
 pub(crate) fn obtain_extensions(
     gltf: &Gltf
-) -> AppResult<&serde_json::map::Map<String, serde_json::Value>> {
-    gltf.source
-        .as_ref()
-        .and_then(|source| source.extensions())
-        .context("Not found gltf extensions")
+) -> Option<&serde_json::map::Map<String, serde_json::Value>> {
+    gltf.source.as_ref()?.extensions()
 }
 
-pub(crate) fn obtain_vrmc_vrm(
-    json: &serde_json::map::Map<String, serde_json::Value>
-) -> AppResult<serde_json::Value> {
-    Ok(json
-        .get("VRMC_vrm")
-        .or_else(|| json.get("VRMC_vrm_animation"))
-        .context("Not found VRMC_vrm or VRMC_vrm_animation")?
-        .clone())
+/// Deserializes the glTF extension with the given name.
+pub(crate) fn parse_extension<T: serde::de::DeserializeOwned>(
+    json: &serde_json::map::Map<String, serde_json::Value>,
+    extension: &'static str,
+) -> Result<T, VrmError> {
+    let value = json
+        .get(extension)
+        .ok_or(VrmError::MissingExtension(extension))?;
+    T::deserialize(value).map_err(|source| VrmError::InvalidExtension { extension, source })
 }
 
-pub(crate) fn obtain_vrmc_springs(
+/// Only the major version 1 of the extensions is supported.
+pub(crate) fn check_spec_version(
+    extension: &'static str,
+    version: &str,
+) -> Result<(), VrmError> {
+    if version.split('.').next() == Some("1") {
+        Ok(())
+    } else {
+        Err(VrmError::UnsupportedSpecVersion {
+            extension,
+            version: version.to_string(),
+        })
+    }
+}
+
+pub(crate) fn check_node_indices(
+    human_bones: &bevy::platform::collections::HashMap<String, VrmNode>,
+    node_count: usize,
+) -> Result<(), VrmError> {
+    match human_bones
+        .iter()
+        .find(|(_, target)| node_count <= target.node)
+    {
+        Some((bone, target)) => Err(VrmError::InvalidNodeIndex {
+            referrer: format!("humanoid bone {bone}"),
+            index: target.node,
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Deserializes the VRM 0.x `VRM` extension if the glTF only has it.
+pub(crate) fn parse_vrm0(
     json: &serde_json::map::Map<String, serde_json::Value>
-) -> AppResult<serde_json::Value> {
-    Ok(json
-        .get("VRMC_springBone")
-        .context("Not found VRMC_springBone")?
-        .clone())
+) -> Result<Option<Vrm0>, VrmError> {
+    if is_vrm0(json) {
+        parse_extension(json, VRM0).map(Some)
+    } else {
+        Ok(None)
+    }
+}
+
+/// Returns `true` if the glTF only has the VRM 0.x extension.
+pub(crate) fn is_vrm0(json: &serde_json::map::Map<String, serde_json::Value>) -> bool {
+    !json.contains_key(VRMC_VRM) && json.contains_key(VRM0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::VrmError;
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::{check_spec_version, VrmExtensions, VRMC_VRM};
+
+    #[test]
+    fn accept_only_major_version_1() {
+        assert!(check_spec_version(VRMC_VRM, "1.0").is_ok());
+        assert!(check_spec_version(VRMC_VRM, "1.0-beta").is_ok());
+        assert!(matches!(
+            check_spec_version(VRMC_VRM, "2.0"),
+            Err(VrmError::UnsupportedSpecVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn ignore_malformed_vrmc_spring_bone() -> TestResult {
+        let json = serde_json::json!({
+            "VRMC_vrm": {
+                "specVersion": "1.0",
+                "humanoid": { "humanBones": {} }
+            },
+            "VRMC_springBone": { "specVersion": "1.0", "springs": 1 }
+        });
+        let extensions = VrmExtensions::new(json.as_object().ok_or("no extensions")?)?;
+        assert!(extensions.vrmc_spring_bone.is_none());
+        success!()
+    }
+
+    #[test]
+    fn missing_vrmc_vrm() {
+        let json = serde_json::Map::new();
+        assert!(matches!(
+            VrmExtensions::new(&json),
+            Err(VrmError::MissingExtension(VRMC_VRM))
+        ));
+    }
 }
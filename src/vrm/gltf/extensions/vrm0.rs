@@ -0,0 +1,769 @@
+//! This module converts the legacy VRM 0.x `VRM` extension into the VRM 1.0 structures.
+//!
+//! - [`VRM 0.x specification`](https://github.com/vrm-c/vrm-specification/tree/master/specification/0.0)
+//!
+//! VRM 0.x models face `-Z` and store their vectors in Unity's left-handed coordinates.
+//! The scene is rotated to face `+Z` while loading in [`scene`],
+//! and the vectors are converted into the space of the rotated scene here.
+
+mod material_properties;
+pub(crate) mod scene;
+pub(crate) mod secondary_animation;
+
+use crate::vrm::gltf::extensions::vrm0::material_properties::Vrm0MaterialProperty;
+use crate::vrm::gltf::extensions::vrm0::secondary_animation::Vrm0SecondaryAnimation;
+use crate::vrm::gltf::extensions::vrmc_spring_bone::VRMCSpringBone;
+use crate::vrm::gltf::extensions::vrmc_vrm::{
+    ExpressionOverrideType, Expressions, FirstPerson, Humanoid, LookAtProperties, LookAtType,
+    MaterialColorBind, MaterialColorType, Meta, MorphTargetBind, RangeMap, Struct6,
+    TextureTransformBind, VrmPreset, VrmcVrm,
+};
+use crate::vrm::gltf::extensions::VrmNode;
+use crate::vrm::gltf::materials::VrmcMaterialsExtensitions;
+use bevy::color::{ColorToComponents, LinearRgba, Srgba};
+use bevy::gltf::Gltf;
+use bevy::platform::collections::HashMap;
+use serde::{Deserialize, Serialize};
+
+/// The root of the VRM 0.x `VRM` extension.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0 {
+    #[serde(rename = "specVersion")]
+    pub spec_version: Option<String>,
+    pub meta: Option<Vrm0Meta>,
+    pub humanoid: Vrm0Humanoid,
+    #[serde(rename = "firstPerson")]
+    pub first_person: Option<Vrm0FirstPerson>,
+    #[serde(rename = "blendShapeMaster")]
+    pub blend_shape_master: Option<Vrm0BlendShapeMaster>,
+    #[serde(rename = "secondaryAnimation")]
+    pub secondary_animation: Option<Vrm0SecondaryAnimation>,
+    #[serde(rename = "materialProperties")]
+    pub material_properties: Vec<Vrm0MaterialProperty>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0Meta {
+    pub title: Option<String>,
+    pub version: Option<String>,
+    pub author: Option<String>,
+    #[serde(rename = "contactInformation")]
+    pub contact_information: Option<String>,
+    pub reference: Option<String>,
+    /// The index of the thumbnail texture.
+    pub texture: Option<usize>,
+    #[serde(rename = "allowedUserName")]
+    pub allowed_user_name: Option<String>,
+    #[serde(rename = "violentUssageName")]
+    pub violent_usage_name: Option<String>,
+    #[serde(rename = "sexualUssageName")]
+    pub sexual_usage_name: Option<String>,
+    #[serde(rename = "commercialUssageName")]
+    pub commercial_usage_name: Option<String>,
+    #[serde(rename = "otherPermissionUrl")]
+    pub other_permission_url: Option<String>,
+    #[serde(rename = "licenseName")]
+    pub license_name: Option<String>,
+    #[serde(rename = "otherLicenseUrl")]
+    pub other_license_url: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0Humanoid {
+    #[serde(rename = "humanBones")]
+    pub human_bones: Vec<Vrm0HumanBone>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Vrm0HumanBone {
+    pub bone: String,
+    pub node: usize,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0FirstPerson {
+    #[serde(rename = "firstPersonBoneOffset")]
+    pub first_person_bone_offset: Vrm0Vec3,
+    #[serde(rename = "meshAnnotations")]
+    pub mesh_annotations: Vec<Vrm0MeshAnnotation>,
+    #[serde(rename = "lookAtTypeName")]
+    pub look_at_type_name: Option<String>,
+    #[serde(rename = "lookAtHorizontalInner")]
+    pub look_at_horizontal_inner: Option<Vrm0DegreeMap>,
+    #[serde(rename = "lookAtHorizontalOuter")]
+    pub look_at_horizontal_outer: Option<Vrm0DegreeMap>,
+    #[serde(rename = "lookAtVerticalDown")]
+    pub look_at_vertical_down: Option<Vrm0DegreeMap>,
+    #[serde(rename = "lookAtVerticalUp")]
+    pub look_at_vertical_up: Option<Vrm0DegreeMap>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Vrm0MeshAnnotation {
+    pub mesh: usize,
+    #[serde(rename = "firstPersonFlag")]
+    pub first_person_flag: String,
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone)]
+pub(crate) struct Vrm0DegreeMap {
+    #[serde(rename = "xRange")]
+    pub x_range: f32,
+    #[serde(rename = "yRange")]
+    pub y_range: f32,
+}
+
+impl From<Vrm0DegreeMap> for RangeMap {
+    fn from(map: Vrm0DegreeMap) -> Self {
+        Self {
+            input_max_value: map.x_range,
+            output_scale: map.y_range,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0BlendShapeMaster {
+    #[serde(rename = "blendShapeGroups")]
+    pub blend_shape_groups: Vec<Vrm0BlendShapeGroup>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0BlendShapeGroup {
+    pub name: String,
+    #[serde(rename = "presetName")]
+    pub preset_name: String,
+    pub binds: Vec<Vrm0BlendShapeBind>,
+    #[serde(rename = "materialValues")]
+    pub material_values: Vec<Vrm0MaterialValue>,
+    #[serde(rename = "isBinary")]
+    pub is_binary: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub(crate) struct Vrm0BlendShapeBind {
+    pub mesh: usize,
+    pub index: usize,
+    /// The weight of the morph target in the range of `0` to `100`.
+    pub weight: f32,
+}
+
+/// A material property bound to the blend shape group.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0MaterialValue {
+    #[serde(rename = "materialName")]
+    pub material_name: String,
+    /// The name of the Unity shader property, such as `_Color` or `_MainTex_ST`.
+    #[serde(rename = "propertyName")]
+    pub property_name: String,
+    /// The value when the weight is `1.0`; the colors are in sRGB.
+    #[serde(rename = "targetValue")]
+    pub target_value: Vec<f32>,
+}
+
+/// A vector in Unity's coordinate system.
+#[derive(Serialize, Deserialize, Default, Copy, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub(crate) struct Vrm0Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vrm0Vec3 {
+    /// Converts the vector from Unity's coordinate system into the space of the rotated scene.
+    ///
+    /// glTF negates Z of Unity, and rotating the scene around the Y axis negates X and Z again.
+    #[inline]
+    pub fn to_vrm1(self) -> [f32; 3] {
+        [-self.x, self.y, self.z]
+    }
+}
+
+/// The parts of the glTF document that VRM 0.x conversion depends on.
+///
+/// VRM 0.x references meshes and textures where VRM 1.0 references nodes and images.
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct GltfDocument {
+    pub nodes: Vec<GltfDocumentNode>,
+    pub meshes: Vec<GltfDocumentMesh>,
+    pub materials: Vec<GltfDocumentMaterial>,
+    pub textures: Vec<GltfDocumentTexture>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct GltfDocumentNode {
+    pub mesh: Option<usize>,
+    pub children: Vec<usize>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct GltfDocumentMesh {
+    pub primitives: Vec<GltfDocumentPrimitive>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct GltfDocumentPrimitive {
+    /// The accessor indices of the morph targets keyed by the attribute name.
+    pub targets: Vec<HashMap<String, usize>>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct GltfDocumentMaterial {
+    pub name: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
+#[serde(default)]
+pub(crate) struct GltfDocumentTexture {
+    pub source: Option<usize>,
+}
+
+impl GltfDocument {
+    /// Returns the indices of the nodes that instantiate the mesh.
+    pub fn mesh_nodes(
+        &self,
+        mesh: usize,
+    ) -> impl Iterator<Item = usize> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(move |(_, node)| node.mesh == Some(mesh))
+            .map(|(index, _)| index)
+    }
+
+    /// Returns the index of the material with the name.
+    pub fn material_index(
+        &self,
+        name: &str,
+    ) -> Option<usize> {
+        self.materials
+            .iter()
+            .position(|material| material.name.as_deref() == Some(name))
+    }
+
+    /// Returns the number of morph targets of the mesh instantiated by the node.
+    ///
+    /// Returns `None` if the node does not exist or has no mesh.
+    pub fn morph_target_count(
+        &self,
+        node: usize,
+    ) -> Option<usize> {
+        let mesh = self.meshes.get(self.nodes.get(node)?.mesh?)?;
+        Some(
+            mesh.primitives
+                .iter()
+                .map(|primitive| primitive.targets.len())
+                .max()
+                .unwrap_or_default(),
+        )
+    }
+}
+
+impl From<&Gltf> for GltfDocument {
+    fn from(gltf: &Gltf) -> Self {
+        let Some(source) = gltf.source.as_ref() else {
+            return Self::default();
+        };
+        Self {
+            nodes: source
+                .nodes()
+                .map(|node| GltfDocumentNode {
+                    mesh: node.mesh().map(|mesh| mesh.index()),
+                    children: node.children().map(|child| child.index()).collect(),
+                })
+                .collect(),
+            meshes: source
+                .meshes()
+                .map(|mesh| GltfDocumentMesh {
+                    primitives: mesh
+                        .primitives()
+                        .map(|primitive| GltfDocumentPrimitive {
+                            targets: primitive
+                                .morph_targets()
+                                .map(|target| {
+                                    [
+                                        ("POSITION", target.positions()),
+                                        ("NORMAL", target.normals()),
+                                        ("TANGENT", target.tangents()),
+                                    ]
+                                    .into_iter()
+                                    .filter_map(|(attribute, accessor)| {
+                                        Some((attribute.to_string(), accessor?.index()))
+                                    })
+                                    .collect()
+                                })
+                                .collect(),
+                        })
+                        .collect(),
+                })
+                .collect(),
+            materials: source
+                .materials()
+                .map(|material| GltfDocumentMaterial {
+                    name: material.name().map(ToString::to_string),
+                })
+                .collect(),
+            textures: source
+                .textures()
+                .map(|texture| GltfDocumentTexture {
+                    source: Some(texture.source().index()),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Vrm0 {
+    pub fn to_vrmc_vrm(
+        &self,
+        document: &GltfDocument,
+    ) -> VrmcVrm {
+        VrmcVrm {
+            expressions: self
+                .blend_shape_master
+                .as_ref()
+                .map(|master| master.to_expressions(document)),
+            first_person: self
+                .first_person
+                .as_ref()
+                .map(|first_person| first_person.to_first_person(document)),
+            humanoid: self.humanoid.to_humanoid(),
+            look_at: self.first_person.as_ref().map(Vrm0FirstPerson::to_look_at),
+            meta: self.meta.as_ref().map(|meta| meta.to_meta(document)),
+            spec_version: self
+                .spec_version
+                .clone()
+                .unwrap_or_else(|| "0.0".to_string()),
+        }
+    }
+
+    /// Converts `secondaryAnimation` into `VRMC_springBone`.
+    ///
+    /// Returns `None` if the model has no spring bones.
+    pub fn to_vrmc_spring_bone(
+        &self,
+        document: &GltfDocument,
+    ) -> Option<VRMCSpringBone> {
+        self.secondary_animation
+            .as_ref()
+            .map(|secondary_animation| secondary_animation.to_vrmc_spring_bone(document))
+    }
+
+    /// Converts the `MToon` properties of the glTF material into `VRMC_materials_mtoon`.
+    pub fn to_vrmc_materials_mtoon(
+        &self,
+        material_index: usize,
+    ) -> Option<VrmcMaterialsExtensitions> {
+        self.material_properties.get(material_index)?.to_mtoon()
+    }
+}
+
+impl Vrm0Humanoid {
+    fn to_humanoid(&self) -> Humanoid {
+        Humanoid {
+            human_bones: self
+                .human_bones
+                .iter()
+                .map(|bone| (convert_bone_name(&bone.bone), VrmNode { node: bone.node }))
+                .collect(),
+        }
+    }
+}
+
+/// VRM 1.0 added `thumbMetacarpal`, so the thumb bones of VRM 0.x are shifted by one.
+fn convert_bone_name(bone: &str) -> String {
+    match bone {
+        "leftThumbProximal" => "leftThumbMetacarpal",
+        "leftThumbIntermediate" => "leftThumbProximal",
+        "rightThumbProximal" => "rightThumbMetacarpal",
+        "rightThumbIntermediate" => "rightThumbProximal",
+        other => other,
+    }
+    .to_string()
+}
+
+impl Vrm0BlendShapeMaster {
+    fn to_expressions(
+        &self,
+        document: &GltfDocument,
+    ) -> Expressions {
+        let mut expressions = Expressions::default();
+        for group in &self.blend_shape_groups {
+            // Groups with an unknown preset are the expressions defined by the author.
+            match convert_preset_name(&group.preset_name) {
+                Some(preset_name) => {
+                    expressions
+                        .preset
+                        .insert(preset_name.to_string(), group.to_preset(document));
+                }
+                None if !group.name.is_empty() => {
+                    expressions
+                        .custom
+                        .insert(group.name.clone(), group.to_preset(document));
+                }
+                None => {}
+            }
+        }
+        expressions
+    }
+}
+
+impl Vrm0BlendShapeGroup {
+    fn to_preset(
+        &self,
+        document: &GltfDocument,
+    ) -> VrmPreset {
+        VrmPreset {
+            is_binary: self.is_binary,
+            morph_target_binds: Some(
+                self.binds
+                    .iter()
+                    .flat_map(|bind| {
+                        document.mesh_nodes(bind.mesh).map(|node| MorphTargetBind {
+                            node,
+                            index: bind.index,
+                            weight: bind.weight / 100.,
+                        })
+                    })
+                    .collect(),
+            ),
+            material_color_binds: Some(
+                self.material_values
+                    .iter()
+                    .filter_map(|value| value.to_material_color_bind(document))
+                    .collect(),
+            ),
+            texture_transform_binds: Some(
+                self.material_values
+                    .iter()
+                    .filter_map(|value| value.to_texture_transform_bind(document))
+                    .collect(),
+            ),
+            override_blink: ExpressionOverrideType::None,
+            override_look_at: ExpressionOverrideType::None,
+            override_mouth: ExpressionOverrideType::None,
+        }
+    }
+}
+
+impl Vrm0MaterialValue {
+    /// Converts the color property into the bind of VRM 1.0, whose target value is in linear space.
+    ///
+    /// Returns `None` if the property is not a color that VRM 1.0 can bind.
+    fn to_material_color_bind(
+        &self,
+        document: &GltfDocument,
+    ) -> Option<MaterialColorBind> {
+        let r#type = match self.property_name.as_str() {
+            "_Color" => MaterialColorType::Color,
+            "_EmissionColor" => MaterialColorType::EmissionColor,
+            "_ShadeColor" => MaterialColorType::ShadeColor,
+            "_RimColor" => MaterialColorType::RimColor,
+            "_OutlineColor" => MaterialColorType::OutlineColor,
+            _ => return None,
+        };
+        let [r, g, b, a] = self.target_value()?;
+        Some(MaterialColorBind {
+            material: document.material_index(&self.material_name)?,
+            r#type,
+            target_value: LinearRgba::from(Srgba::new(r, g, b, a)).to_f32_array(),
+        })
+    }
+
+    /// Converts the scale and the offset of the main texture into the bind of VRM 1.0.
+    ///
+    /// The V axis of Unity points up while that of glTF points down, so the offset is flipped vertically.
+    fn to_texture_transform_bind(
+        &self,
+        document: &GltfDocument,
+    ) -> Option<TextureTransformBind> {
+        if self.property_name != "_MainTex_ST" {
+            return None;
+        }
+        let [scale_x, scale_y, offset_x, offset_y] = self.target_value()?;
+        Some(TextureTransformBind {
+            material: document.material_index(&self.material_name)?,
+            scale: [scale_x, scale_y],
+            offset: [offset_x, 1. - offset_y - scale_y],
+        })
+    }
+
+    fn target_value(&self) -> Option<[f32; 4]> {
+        self.target_value.get(..4)?.try_into().ok()
+    }
+}
+
+/// Converts the preset name of VRM 0.x into the preset name of VRM 1.0.
+///
+/// Returns `None` if the preset is `unknown`; such groups are converted into custom expressions.
+fn convert_preset_name(preset_name: &str) -> Option<&'static str> {
+    Some(match preset_name.to_lowercase().as_str() {
+        "neutral" => "neutral",
+        "a" => "aa",
+        "i" => "ih",
+        "u" => "ou",
+        "e" => "ee",
+        "o" => "oh",
+        "blink" => "blink",
+        "blink_l" => "blinkLeft",
+        "blink_r" => "blinkRight",
+        "joy" => "happy",
+        "angry" => "angry",
+        "sorrow" => "sad",
+        "fun" => "relaxed",
+        "lookup" => "lookUp",
+        "lookdown" => "lookDown",
+        "lookleft" => "lookLeft",
+        "lookright" => "lookRight",
+        _ => return None,
+    })
+}
+
+impl Vrm0FirstPerson {
+    fn to_first_person(
+        &self,
+        document: &GltfDocument,
+    ) -> FirstPerson {
+        FirstPerson {
+            mesh_annotations: self
+                .mesh_annotations
+                .iter()
+                .flat_map(|annotation| {
+                    let r#type = match annotation.first_person_flag.as_str() {
+                        "Both" => "both",
+                        "ThirdPersonOnly" => "thirdPersonOnly",
+                        "FirstPersonOnly" => "firstPersonOnly",
+                        _ => "auto",
+                    };
+                    document
+                        .mesh_nodes(annotation.mesh)
+                        .map(move |node| Struct6 {
+                            node: node as i64,
+                            r#type: r#type.to_string(),
+                        })
+                })
+                .collect(),
+        }
+    }
+
+    fn to_look_at(&self) -> LookAtProperties {
+        let r#type = match self.look_at_type_name.as_deref() {
+            Some("BlendShape") => LookAtType::Expression,
+            _ => LookAtType::Bone,
+        };
+        // The default curves of UniVRM 0.x.
+        let default_map = match r#type {
+            LookAtType::Bone => Vrm0DegreeMap {
+                x_range: 90.,
+                y_range: 10.,
+            },
+            LookAtType::Expression => Vrm0DegreeMap {
+                x_range: 90.,
+                y_range: 1.,
+            },
+        };
+        LookAtProperties {
+            offset_from_head_bone: self.first_person_bone_offset.to_vrm1(),
+            range_map_horizontal_inner: self.look_at_horizontal_inner.unwrap_or(default_map).into(),
+            range_map_horizontal_outer: self.look_at_horizontal_outer.unwrap_or(default_map).into(),
+            range_map_vertical_down: self.look_at_vertical_down.unwrap_or(default_map).into(),
+            range_map_vertical_up: self.look_at_vertical_up.unwrap_or(default_map).into(),
+            r#type,
+        }
+    }
+}
+
+impl Vrm0Meta {
+    /// Returns the URL of the license document for `licenseName`.
+    ///
+    /// The licenses other than Creative Commons are converted into the VRM Public License,
+    /// and their terms are kept in `otherLicenseUrl`.
+    fn license_url(license_name: &str) -> &'static str {
+        match license_name {
+            "CC0" => "https://creativecommons.org/publicdomain/zero/1.0/",
+            "CC_BY" => "https://creativecommons.org/licenses/by/4.0/",
+            "CC_BY_NC" => "https://creativecommons.org/licenses/by-nc/4.0/",
+            "CC_BY_SA" => "https://creativecommons.org/licenses/by-sa/4.0/",
+            "CC_BY_NC_SA" => "https://creativecommons.org/licenses/by-nc-sa/4.0/",
+            "CC_BY_ND" => "https://creativecommons.org/licenses/by-nd/4.0/",
+            "CC_BY_NC_ND" => "https://creativecommons.org/licenses/by-nc-nd/4.0/",
+            _ => "https://vrm.dev/licenses/1.0/",
+        }
+    }
+
+    fn to_meta(
+        &self,
+        document: &GltfDocument,
+    ) -> Meta {
+        let license_name = self.license_name.as_deref().unwrap_or("Other");
+        let is_creative_commons = license_name.starts_with("CC");
+        let non_commercial = license_name.contains("_NC");
+        let allowed = |usage: &Option<String>| usage.as_deref() == Some("Allow");
+        Meta {
+            allow_antisocial_or_hate_usage: false,
+            allow_excessively_sexual_usage: allowed(&self.sexual_usage_name),
+            allow_excessively_violent_usage: allowed(&self.violent_usage_name),
+            allow_political_or_religious_usage: false,
+            allow_redistribution: is_creative_commons,
+            authors: self.author.clone().into_iter().collect(),
+            avatar_permission: Some(
+                match self.allowed_user_name.as_deref() {
+                    Some("Everyone") => "everyone",
+                    Some("ExplicitlyLicensedPerson") => "onlySeparatelyLicensedPerson",
+                    _ => "onlyAuthor",
+                }
+                .to_string(),
+            ),
+            // VRM 0.x has no notion of corporate usage,
+            // so `Allow` is converted into the narrowest commercial permission.
+            commercial_usage: Some(
+                if allowed(&self.commercial_usage_name) && !non_commercial {
+                    "personalProfit"
+                } else {
+                    "personalNonProfit"
+                }
+                .to_string(),
+            ),
+            credit_notation: Some(
+                if license_name == "CC0" {
+                    "unnecessary"
+                } else {
+                    "required"
+                }
+                .to_string(),
+            ),
+            license_url: Some(Self::license_url(license_name).to_string()),
+            modification: Some(
+                if is_creative_commons && !license_name.contains("_ND") {
+                    "allowModificationRedistribution"
+                } else {
+                    "prohibited"
+                }
+                .to_string(),
+            ),
+            name: self.title.clone(),
+            other_license_url: self
+                .other_license_url
+                .clone()
+                .or_else(|| self.other_permission_url.clone()),
+            thumbnail_image: self
+                .texture
+                .and_then(|texture| document.textures.get(texture)?.source)
+                .map(|image| image as i64),
+            version: self.version.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::vrm0::{
+        GltfDocument, GltfDocumentMaterial, GltfDocumentNode, Vrm0, Vrm0Meta, Vrm0Vec3,
+    };
+    use crate::vrm::gltf::extensions::vrmc_vrm::{LookAtType, MaterialColorType};
+
+    fn document() -> GltfDocument {
+        GltfDocument {
+            nodes: vec![
+                GltfDocumentNode::default(),
+                GltfDocumentNode {
+                    mesh: Some(0),
+                    children: Vec::new(),
+                },
+            ],
+            materials: ["Body", "Face"]
+                .map(|name| GltfDocumentMaterial {
+                    name: Some(name.to_string()),
+                })
+                .to_vec(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn convert_vrm0() -> TestResult {
+        let vrm0: Vrm0 = serde_json::from_str(include_str!("vrm0.json"))?;
+        let vrmc_vrm = vrm0.to_vrmc_vrm(&document());
+
+        assert_eq!(vrmc_vrm.spec_version, "0.0");
+        assert_eq!(vrmc_vrm.humanoid.human_bones["hips"].node, 0);
+        assert_eq!(vrmc_vrm.humanoid.human_bones["leftThumbMetacarpal"].node, 2);
+        assert!(!vrmc_vrm
+            .humanoid
+            .human_bones
+            .contains_key("leftThumbIntermediate"));
+
+        let expressions = vrmc_vrm.expressions.expect("expressions");
+        let binds = expressions.preset["happy"]
+            .morph_target_binds
+            .as_ref()
+            .expect("binds");
+        assert_eq!(binds[0].node, 1);
+        assert!((binds[0].weight - 1.0).abs() < f32::EPSILON);
+        let colors = expressions.preset["happy"]
+            .material_color_binds
+            .as_ref()
+            .expect("material color binds");
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[0].material, 1);
+        assert_eq!(colors[0].r#type, MaterialColorType::Color);
+        assert!((colors[0].target_value[1] - 0.214).abs() < 1e-3);
+        let transforms = expressions.preset["happy"]
+            .texture_transform_binds
+            .as_ref()
+            .expect("texture transform binds");
+        assert_eq!(transforms[0].scale, [0.5, 0.5]);
+        assert_eq!(transforms[0].offset, [0.25, 0.25]);
+        assert!(expressions.preset.contains_key("aa"));
+        assert!(!expressions.preset.contains_key("unknown"));
+        assert!(expressions.custom.contains_key("Tongue"));
+
+        let look_at = vrmc_vrm.look_at.expect("look at");
+        assert_eq!(look_at.r#type, LookAtType::Bone);
+        assert_eq!(look_at.offset_from_head_bone, [0.0, 0.06, 0.02]);
+        assert_eq!(look_at.range_map_horizontal_outer.output_scale, 12.);
+
+        let meta = vrmc_vrm.meta.expect("meta");
+        assert_eq!(meta.name.as_deref(), Some("Alicia"));
+        assert_eq!(meta.avatar_permission.as_deref(), Some("everyone"));
+        assert!(!meta.allow_redistribution);
+        assert_eq!(
+            meta.license_url.as_deref(),
+            Some("https://vrm.dev/licenses/1.0/")
+        );
+        assert_eq!(
+            meta.other_license_url.as_deref(),
+            Some("https://3d.nicovideo.jp/alicia/rule.html")
+        );
+        assert_eq!(
+            Vrm0Meta::license_url("CC_BY_NC"),
+            "https://creativecommons.org/licenses/by-nc/4.0/"
+        );
+        success!()
+    }
+
+    #[test]
+    fn unity_to_vrm1_coordinates() {
+        let v = Vrm0Vec3 {
+            x: 1.,
+            y: 2.,
+            z: 3.,
+        };
+        assert_eq!(v.to_vrm1(), [-1., 2., 3.]);
+    }
+}
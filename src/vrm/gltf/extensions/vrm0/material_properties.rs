@@ -0,0 +1,281 @@
+//! Converts the `MToon` parameters of VRM 0.x into `VRMC_materials_mtoon`.
+//!
+//! VRM 0.x stores the properties of the Unity shader as they are,
+//! so colors are in sRGB, the outline width is in centimeters, and the V axis of UVs is flipped.
+//! The conversion follows the migration of `UniVRM`.
+
+use crate::vrm::gltf::materials::{
+    KhrTextureTransform, MatcapTexture, OutlineWidthMultiplyTexture, RimMultiplyTexture,
+    UVAnimationMaskTexture, VrmTexture, VrmTextureExtensions, VrmcMaterialsExtensitions,
+};
+use bevy::color::{Color, LinearRgba};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+const MTOON_SHADER: &str = "VRM/MToon";
+
+/// The render queue of `Transparent` in Unity.
+const TRANSPARENT_RENDER_QUEUE: i32 = 3000;
+
+/// The render queue of `TransparentWithZWrite` in `MToon` 0.x.
+const TRANSPARENT_WITH_Z_WRITE_RENDER_QUEUE: i32 = 2501;
+
+/// An element of `materialProperties`.
+///
+/// The index in the array corresponds to the index of the glTF material.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0MaterialProperty {
+    pub name: String,
+    pub shader: String,
+    #[serde(rename = "renderQueue")]
+    pub render_queue: i32,
+    #[serde(rename = "floatProperties")]
+    pub float_properties: HashMap<String, f32>,
+    #[serde(rename = "vectorProperties")]
+    pub vector_properties: HashMap<String, Vec<f32>>,
+    /// The values are the indices of glTF textures.
+    #[serde(rename = "textureProperties")]
+    pub texture_properties: HashMap<String, usize>,
+}
+
+/// `_BlendMode` of `MToon` 0.x.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Vrm0BlendMode {
+    Opaque,
+    Cutout,
+    Transparent,
+    TransparentWithZWrite,
+}
+
+impl Vrm0MaterialProperty {
+    /// Converts the properties into `VRMC_materials_mtoon`.
+    ///
+    /// Returns `None` if the material does not use the `MToon` shader.
+    pub fn to_mtoon(&self) -> Option<VrmcMaterialsExtensitions> {
+        if self.shader != MTOON_SHADER {
+            return None;
+        }
+        let shading_toony = self.float("_ShadeToony", 0.9);
+        let shading_shift = self.float("_ShadeShift", 0.);
+        let blend_mode = self.blend_mode();
+        let has_matcap = self.texture_properties.contains_key("_SphereAdd");
+        Some(VrmcMaterialsExtensitions {
+            spec_version: "1.0".to_string(),
+            matcap_factor: if has_matcap {
+                [1., 1., 1.]
+            } else {
+                [0., 0., 0.]
+            },
+            matcap_texture: self
+                .texture("_SphereAdd")
+                .map(|index| MatcapTexture { index }),
+            parametric_rim_fresnel_power: self.float("_RimFresnelPower", 1.),
+            rim_multiply_texture: self
+                .texture("_RimTexture")
+                .map(|index| RimMultiplyTexture { index }),
+            outline_color_factor: self.color("_OutlineColor", [0., 0., 0.]),
+            outline_lighting_mix_factor: match self.float("_OutlineColorMode", 0.) as i32 {
+                // MixedLighting
+                1 => self.float("_OutlineLightingMix", 1.),
+                // FixedColor
+                _ => 0.,
+            },
+            outline_width_factor: Some(self.float("_OutlineWidth", 0.5) * 0.01),
+            outline_width_multiply_texture: self
+                .texture("_OutlineWidthTexture")
+                .map(|index| OutlineWidthMultiplyTexture { index }),
+            outline_width_mode: match self.float("_OutlineWidthMode", 0.) as i32 {
+                1 => "worldCoordinates",
+                2 => "screenCoordinates",
+                _ => "none",
+            }
+            .to_string(),
+            parametric_rim_color_factor: self.color("_RimColor", [0., 0., 0.]),
+            parametric_rim_lift_factor: self.float("_RimLift", 0.),
+            rim_lighting_mix_factor: self.float("_RimLightingMix", 0.),
+            shade_color_factor: self.color("_ShadeColor", [0.97, 0.81, 0.86]),
+            shade_multiply_texture: self.texture("_ShadeTexture").map(|index| VrmTexture {
+                extensions: VrmTextureExtensions {
+                    khr_texture_transform: self.main_texture_transform(),
+                },
+                index,
+            }),
+            render_queue_offset_number: self.render_queue_offset(blend_mode) as f32,
+            shading_shift_factor: migrate_shading_shift(shading_toony, shading_shift),
+            shading_shift_texture: None,
+            shading_toony_factor: migrate_shading_toony(shading_toony, shading_shift),
+            transparent_with_z_write: blend_mode == Vrm0BlendMode::TransparentWithZWrite,
+            uv_animation_mask_texture: self
+                .texture("_UvAnimMaskTexture")
+                .map(|index| UVAnimationMaskTexture { index }),
+            // 0.x is in rotations per second, and 1.0 is in radians per second.
+            uv_animation_rotation_speed_factor: self.float("_UvAnimRotation", 0.) * TAU,
+            uv_animation_scroll_x_speed_factor: self.float("_UvAnimScrollX", 0.),
+            uv_animation_scroll_y_speed_factor: -self.float("_UvAnimScrollY", 0.),
+            gi_equalization_factor: 1. - self.float("_IndirectLightIntensity", 0.1),
+        })
+    }
+
+    fn blend_mode(&self) -> Vrm0BlendMode {
+        match self.float("_BlendMode", 0.) as i32 {
+            1 => Vrm0BlendMode::Cutout,
+            2 => Vrm0BlendMode::Transparent,
+            3 => Vrm0BlendMode::TransparentWithZWrite,
+            _ => Vrm0BlendMode::Opaque,
+        }
+    }
+
+    /// 0.x has the absolute render queue of Unity,
+    /// whereas 1.0 has the offset from the default queue of the blend mode.
+    fn render_queue_offset(
+        &self,
+        blend_mode: Vrm0BlendMode,
+    ) -> i32 {
+        match blend_mode {
+            Vrm0BlendMode::Opaque | Vrm0BlendMode::Cutout => 0,
+            Vrm0BlendMode::Transparent => {
+                (self.render_queue - TRANSPARENT_RENDER_QUEUE).clamp(-9, 0)
+            }
+            Vrm0BlendMode::TransparentWithZWrite => {
+                (self.render_queue - TRANSPARENT_WITH_Z_WRITE_RENDER_QUEUE).clamp(0, 9)
+            }
+        }
+    }
+
+    /// `_MainTex` holds `[offset_x, offset_y, scale_x, scale_y]` in Unity's UV space.
+    fn main_texture_transform(&self) -> KhrTextureTransform {
+        match self.vector_properties.get("_MainTex").map(Vec::as_slice) {
+            Some([offset_x, offset_y, scale_x, scale_y, ..]) => KhrTextureTransform {
+                offset: [*offset_x, 1. - offset_y - scale_y],
+                scale: [*scale_x, *scale_y],
+            },
+            _ => KhrTextureTransform::default(),
+        }
+    }
+
+    fn float(
+        &self,
+        key: &str,
+        default: f32,
+    ) -> f32 {
+        self.float_properties.get(key).copied().unwrap_or(default)
+    }
+
+    fn texture(
+        &self,
+        key: &str,
+    ) -> Option<usize> {
+        self.texture_properties.get(key).copied()
+    }
+
+    /// Reads the sRGB color and converts it into linear color space.
+    fn color(
+        &self,
+        key: &str,
+        default: [f32; 3],
+    ) -> [f32; 3] {
+        let [r, g, b] = match self.vector_properties.get(key).map(Vec::as_slice) {
+            Some([r, g, b, ..]) => [*r, *g, *b],
+            _ => return default,
+        };
+        let linear = LinearRgba::from(Color::srgb(r, g, b));
+        [linear.red, linear.green, linear.blue]
+    }
+}
+
+/// Returns the range of the shading boundary in 0.x.
+fn shading_range(
+    shading_toony: f32,
+    shading_shift: f32,
+) -> (f32, f32) {
+    let min = shading_shift;
+    let max = 1. + (shading_shift - 1.) * shading_toony;
+    (min, max)
+}
+
+fn migrate_shading_toony(
+    shading_toony: f32,
+    shading_shift: f32,
+) -> f32 {
+    let (min, max) = shading_range(shading_toony, shading_shift);
+    ((2. - (max - min)) * 0.5).clamp(0., 1.)
+}
+
+fn migrate_shading_shift(
+    shading_toony: f32,
+    shading_shift: f32,
+) -> f32 {
+    let (min, max) = shading_range(shading_toony, shading_shift);
+    (-(max + min) * 0.5).clamp(-1., 1.)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::vrm0::material_properties::Vrm0MaterialProperty;
+
+    #[test]
+    fn convert_mtoon_properties() -> TestResult {
+        let property: Vrm0MaterialProperty = serde_json::from_str(
+            r#"{
+                "name": "Hair",
+                "shader": "VRM/MToon",
+                "renderQueue": 2998,
+                "floatProperties": {
+                    "_ShadeToony": 1,
+                    "_ShadeShift": 0,
+                    "_OutlineWidth": 0.2,
+                    "_OutlineWidthMode": 1,
+                    "_OutlineColorMode": 1,
+                    "_OutlineLightingMix": 0.5,
+                    "_BlendMode": 2,
+                    "_UvAnimScrollY": 0.5
+                },
+                "vectorProperties": {
+                    "_ShadeColor": [1, 0, 0.5, 1],
+                    "_MainTex": [0, 0.25, 1, 0.5]
+                },
+                "textureProperties": {
+                    "_MainTex": 0,
+                    "_ShadeTexture": 1,
+                    "_SphereAdd": 2
+                }
+            }"#,
+        )?;
+        let mtoon = property.to_mtoon().expect("MToon");
+
+        assert_eq!(mtoon.shading_toony_factor, 1.);
+        assert_eq!(mtoon.shading_shift_factor, 0.);
+        assert_eq!(mtoon.outline_width_mode, "worldCoordinates");
+        assert!((mtoon.outline_width_factor.unwrap() - 0.002).abs() < 1e-6);
+        assert_eq!(mtoon.outline_lighting_mix_factor, 0.5);
+        assert_eq!(mtoon.render_queue_offset_number, -2.);
+        assert!(!mtoon.transparent_with_z_write);
+        assert_eq!(mtoon.uv_animation_scroll_y_speed_factor, -0.5);
+        assert_eq!(mtoon.matcap_texture.map(|t| t.index), Some(2));
+
+        let [r, g, b] = mtoon.shade_color_factor;
+        assert_eq!((r, g), (1., 0.));
+        assert!((b - 0.214).abs() < 1e-3);
+
+        let shade_texture = mtoon.shade_multiply_texture.expect("shade texture");
+        assert_eq!(shade_texture.index, 1);
+        assert_eq!(
+            shade_texture.extensions.khr_texture_transform.offset,
+            [0., 0.25]
+        );
+        success!()
+    }
+
+    #[test]
+    fn ignore_other_shaders() {
+        let property = Vrm0MaterialProperty {
+            shader: "VRM_USE_GLTFSHADER".to_string(),
+            ..Default::default()
+        };
+        assert!(property.to_mtoon().is_none());
+    }
+}
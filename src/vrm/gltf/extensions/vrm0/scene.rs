@@ -0,0 +1,378 @@
+//! Rotates the glTF scene of VRM 0.x by 180 degrees around the Y axis while loading,
+//! so that the model faces `+Z` and the rest of the pipeline handles it like VRM 1.0.
+//!
+//! As `UniVRM` does when migrating VRM 0.x to 1.0, the transforms of the nodes are conjugated by the rotation,
+//! and the vertices, the morph targets, the inverse bind matrices and the animation keys are rotated.
+//! A bone without rotation keeps it, so its local axes match the models exported as VRM 1.0.
+//! The leaves of the springs are also given the virtual tail nodes that VRM 1.0 needs to swing them.
+
+use crate::error::VrmError;
+use crate::vrm::gltf::extensions::is_vrm0;
+use crate::vrm::gltf::extensions::vrm0::secondary_animation::append_virtual_tails;
+use crate::vrm::gltf::glb::{split_glb, write_glb};
+use bevy::platform::collections::HashMap;
+use serde_json::Value;
+
+/// The quoted key of the VRM 0.x extension in the glTF JSON.
+const VRM0_EXTENSION_KEY: &[u8] = br#""VRM""#;
+
+const BYTE: u64 = 5120;
+const SHORT: u64 = 5122;
+const FLOAT: u64 = 5126;
+
+/// How the elements of an accessor are rotated.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Rotation {
+    /// Vectors and quaternions, whose X and Z components are negated.
+    Vector,
+    /// Column-major 4x4 matrices, which are conjugated by the rotation.
+    Matrix,
+}
+
+impl Rotation {
+    fn is_negated(
+        self,
+        component: usize,
+    ) -> bool {
+        let flipped = |axis: usize| axis == 0 || axis == 2;
+        match self {
+            Self::Vector => flipped(component),
+            Self::Matrix => flipped(component / 4) != flipped(component % 4),
+        }
+    }
+}
+
+/// Returns the GLB with the rotated scene if it is VRM 0.x; otherwise returns the bytes as they are.
+pub(crate) fn rotate_vrm0_scene(bytes: Vec<u8>) -> Result<Vec<u8>, VrmError> {
+    let (json, bin) = split_glb(&bytes)?;
+    // Most files are VRM 1.0, so they are returned before parsing the whole JSON
+    // unless the key of the `VRM` extension may be in it.
+    if !json
+        .windows(VRM0_EXTENSION_KEY.len())
+        .any(|window| window == VRM0_EXTENSION_KEY)
+    {
+        return Ok(bytes);
+    }
+    let mut root: Value =
+        serde_json::from_slice(json).map_err(|e| VrmError::InvalidGltf(e.to_string()))?;
+    if !root
+        .get("extensions")
+        .and_then(Value::as_object)
+        .is_some_and(is_vrm0)
+    {
+        return Ok(bytes);
+    }
+    let mut bin = bin
+        .ok_or_else(|| {
+            VrmError::InvalidGltf("VRM 0.x must be GLB with the binary chunk".to_string())
+        })?
+        .to_vec();
+    rotate_nodes(&mut root);
+    append_virtual_tails(&mut root);
+    for (accessor, rotation) in rotated_accessors(&root) {
+        rotate_accessor(&mut root, &mut bin, accessor, rotation)?;
+    }
+    let json = serde_json::to_vec(&root).map_err(|e| VrmError::InvalidGltf(e.to_string()))?;
+    Ok(write_glb(&json, Some(&bin)))
+}
+
+fn rotate_nodes(root: &mut Value) {
+    let Some(nodes) = root.get_mut("nodes").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for node in nodes {
+        for (key, rotation) in [
+            ("translation", Rotation::Vector),
+            ("rotation", Rotation::Vector),
+            ("matrix", Rotation::Matrix),
+        ] {
+            if let Some(values) = node.get_mut(key).and_then(Value::as_array_mut) {
+                for (i, value) in values.iter_mut().enumerate() {
+                    if rotation.is_negated(i) {
+                        negate_json(value);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn negate_json(value: &mut Value) {
+    if let Some(v) = value.as_f64() {
+        *value = Value::from(-v);
+    }
+}
+
+fn array<'a>(
+    value: &'a Value,
+    key: &str,
+) -> impl Iterator<Item = &'a Value> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+}
+
+fn index(
+    value: &Value,
+    key: &str,
+) -> Option<usize> {
+    value
+        .get(key)
+        .and_then(Value::as_u64)
+        .and_then(|i| usize::try_from(i).ok())
+}
+
+/// Collects the accessors that hold positions, directions or rotations.
+fn rotated_accessors(root: &Value) -> HashMap<usize, Rotation> {
+    let mut accessors = HashMap::new();
+    let mut insert_attributes = |attributes: &Value| {
+        for attribute in ["POSITION", "NORMAL", "TANGENT"] {
+            if let Some(accessor) = index(attributes, attribute) {
+                accessors.insert(accessor, Rotation::Vector);
+            }
+        }
+    };
+    for primitive in array(root, "meshes").flat_map(|mesh| array(mesh, "primitives")) {
+        if let Some(attributes) = primitive.get("attributes") {
+            insert_attributes(attributes);
+        }
+        array(primitive, "targets").for_each(&mut insert_attributes);
+    }
+    for skin in array(root, "skins") {
+        if let Some(accessor) = index(skin, "inverseBindMatrices") {
+            accessors.insert(accessor, Rotation::Matrix);
+        }
+    }
+    for animation in array(root, "animations") {
+        let samplers = animation.get("samplers").and_then(Value::as_array);
+        for channel in array(animation, "channels") {
+            let path = channel
+                .get("target")
+                .and_then(|target| target.get("path"))
+                .and_then(Value::as_str);
+            if !matches!(path, Some("translation" | "rotation")) {
+                continue;
+            }
+            if let Some(output) = index(channel, "sampler")
+                .and_then(|sampler| samplers?.get(sampler))
+                .and_then(|sampler| index(sampler, "output"))
+            {
+                accessors.insert(output, Rotation::Vector);
+            }
+        }
+    }
+    accessors
+}
+
+fn rotate_accessor(
+    root: &mut Value,
+    bin: &mut [u8],
+    accessor_index: usize,
+    rotation: Rotation,
+) -> Result<(), VrmError> {
+    let accessor = root
+        .get("accessors")
+        .and_then(|accessors| accessors.get(accessor_index))
+        .ok_or_else(|| {
+            VrmError::InvalidGltf(format!("Not found the accessor at index {accessor_index}"))
+        })?;
+    let components = match accessor.get("type").and_then(Value::as_str) {
+        Some("VEC3") => 3,
+        Some("VEC4") => 4,
+        Some("MAT4") => 16,
+        _ => return Ok(()),
+    };
+    let component_type = accessor
+        .get("componentType")
+        .and_then(Value::as_u64)
+        .unwrap_or_default();
+    let component_size = match component_type {
+        BYTE => 1,
+        SHORT => 2,
+        FLOAT => 4,
+        _ => {
+            return Err(VrmError::InvalidGltf(format!(
+                "The accessor {accessor_index} of the component type {component_type} cannot be rotated"
+            )));
+        }
+    };
+    let element_size = components * component_size;
+    let negated = (0..components)
+        .filter(|c| rotation.is_negated(*c))
+        .collect::<Vec<_>>();
+    let mut negate_elements = |view: usize, byte_offset: usize, count: usize| {
+        let (view_offset, stride) = buffer_view_layout(root, view, element_size)?;
+        for i in 0..count {
+            let element = view_offset + byte_offset + i * stride;
+            for c in &negated {
+                negate_component(bin, element + c * component_size, component_type)?;
+            }
+        }
+        Ok::<_, VrmError>(())
+    };
+    if let Some(view) = index(accessor, "bufferView") {
+        let count = index(accessor, "count").unwrap_or_default();
+        negate_elements(
+            view,
+            index(accessor, "byteOffset").unwrap_or_default(),
+            count,
+        )?;
+    }
+    if let Some(sparse) = accessor.get("sparse") {
+        let values = sparse.get("values");
+        if let Some(view) = values.and_then(|values| index(values, "bufferView")) {
+            negate_elements(
+                view,
+                values
+                    .and_then(|values| index(values, "byteOffset"))
+                    .unwrap_or_default(),
+                index(sparse, "count").unwrap_or_default(),
+            )?;
+        }
+    }
+
+    // The bounds of the positions are used for the AABB of the meshes.
+    let Some(accessor) = root
+        .get_mut("accessors")
+        .and_then(|accessors| accessors.get_mut(accessor_index))
+        .and_then(Value::as_object_mut)
+    else {
+        return Ok(());
+    };
+    if let (Some(Value::Array(min)), Some(Value::Array(max))) =
+        (accessor.get("min").cloned(), accessor.get("max").cloned())
+    {
+        let (mut new_min, mut new_max) = (min.clone(), max.clone());
+        for c in &negated {
+            if let (Some(lower), Some(upper)) = (min.get(*c), max.get(*c)) {
+                new_min[*c] = upper.clone();
+                new_max[*c] = lower.clone();
+                negate_json(&mut new_min[*c]);
+                negate_json(&mut new_max[*c]);
+            }
+        }
+        accessor.insert("min".to_string(), Value::Array(new_min));
+        accessor.insert("max".to_string(), Value::Array(new_max));
+    }
+    Ok(())
+}
+
+/// Returns the offset in the binary chunk and the byte stride of the buffer view.
+fn buffer_view_layout(
+    root: &Value,
+    view_index: usize,
+    element_size: usize,
+) -> Result<(usize, usize), VrmError> {
+    let view = root
+        .get("bufferViews")
+        .and_then(|views| views.get(view_index))
+        .ok_or_else(|| {
+            VrmError::InvalidGltf(format!("Not found the buffer view at index {view_index}"))
+        })?;
+    let buffer = index(view, "buffer").unwrap_or_default();
+    // The binary chunk of GLB is the buffer without `uri`.
+    if root
+        .get("buffers")
+        .and_then(|buffers| buffers.get(buffer))
+        .is_none_or(|buffer| buffer.get("uri").is_some())
+    {
+        return Err(VrmError::InvalidGltf(format!(
+            "The buffer view {view_index} does not refer to the binary chunk"
+        )));
+    }
+    Ok((
+        index(view, "byteOffset").unwrap_or_default(),
+        index(view, "byteStride").unwrap_or(element_size),
+    ))
+}
+
+fn negate_component(
+    bin: &mut [u8],
+    offset: usize,
+    component_type: u64,
+) -> Result<(), VrmError> {
+    let out_of_range = || VrmError::InvalidGltf("The accessor is out of the buffer".to_string());
+    match component_type {
+        FLOAT => {
+            let bytes = bin.get_mut(offset..offset + 4).ok_or_else(out_of_range)?;
+            let value = f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+            bytes.copy_from_slice(&(-value).to_le_bytes());
+        }
+        SHORT => {
+            let bytes = bin.get_mut(offset..offset + 2).ok_or_else(out_of_range)?;
+            let value = i16::from_le_bytes([bytes[0], bytes[1]]);
+            bytes.copy_from_slice(&value.saturating_neg().to_le_bytes());
+        }
+        _ => {
+            let byte = bin.get_mut(offset).ok_or_else(out_of_range)?;
+            *byte = (*byte as i8).saturating_neg() as u8;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::vrm0::scene::rotate_vrm0_scene;
+    use crate::vrm::gltf::glb::{split_glb, write_glb};
+    use serde_json::{json, Value};
+
+    fn floats(bin: &[u8]) -> Vec<f32> {
+        bin.chunks_exact(4)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect()
+    }
+
+    #[test]
+    fn rotate_vrm0_glb() -> TestResult {
+        let positions = [1.0f32, 2.0, 3.0, -4.0, 5.0, 6.0];
+        let bin = positions
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        let json = json!({
+            "extensionsUsed": ["VRM"],
+            "extensions": { "VRM": {} },
+            "nodes": [{ "translation": [1.0, 2.0, 3.0], "rotation": [0.1, 0.2, 0.3, 0.9], "mesh": 0 }],
+            "meshes": [{ "primitives": [{ "attributes": { "POSITION": 0 } }] }],
+            "accessors": [{
+                "bufferView": 0, "componentType": 5126, "count": 2, "type": "VEC3",
+                "min": [-4.0, 2.0, 3.0], "max": [1.0, 5.0, 6.0]
+            }],
+            "bufferViews": [{ "buffer": 0, "byteLength": 24 }],
+            "buffers": [{ "byteLength": 24 }]
+        });
+        let glb = write_glb(&serde_json::to_vec(&json)?, Some(&bin));
+
+        let rotated = rotate_vrm0_scene(glb)?;
+        let (json, bin) = split_glb(&rotated)?;
+        let json: Value = serde_json::from_slice(json)?;
+        assert_eq!(json["nodes"][0]["translation"], json!([-1.0, 2.0, -3.0]));
+        assert_eq!(json["nodes"][0]["rotation"], json!([-0.1, 0.2, -0.3, 0.9]));
+        assert_eq!(json["accessors"][0]["min"], json!([-1.0, 2.0, -6.0]));
+        assert_eq!(json["accessors"][0]["max"], json!([4.0, 5.0, -3.0]));
+        assert_eq!(
+            floats(bin.unwrap_or_default()),
+            vec![-1.0, 2.0, -3.0, 4.0, 5.0, -6.0]
+        );
+        success!()
+    }
+
+    #[test]
+    fn keep_vrm1_glb() -> TestResult {
+        let glb = write_glb(br#"{"extensions":{"VRMC_vrm":{}}}"#, None);
+        assert_eq!(rotate_vrm0_scene(glb.clone())?, glb);
+
+        // A node named `VRM` is not mistaken for the extension.
+        let glb = write_glb(
+            br#"{"nodes":[{"name":"VRM"}],"extensions":{"VRMC_vrm":{}}}"#,
+            None,
+        );
+        assert_eq!(rotate_vrm0_scene(glb.clone())?, glb);
+        success!()
+    }
+}
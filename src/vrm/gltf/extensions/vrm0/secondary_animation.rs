@@ -0,0 +1,405 @@
+//! Converts `secondaryAnimation` of VRM 0.x into `VRMC_springBone`.
+
+use crate::vrm::gltf::extensions::vrm0::{GltfDocument, Vrm0Vec3};
+use crate::vrm::gltf::extensions::vrmc_spring_bone::{
+    Collider, ColliderGroup, ColliderShape, Sphere, Spring, SpringJoint, VRMCSpringBone,
+};
+use bevy::math::{Mat4, Quat, Vec3};
+use bevy::platform::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The length of the virtual tail that `UniVRM` appends to the leaves of the springs.
+const VIRTUAL_TAIL_LENGTH: f32 = 0.07;
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0SecondaryAnimation {
+    #[serde(rename = "boneGroups")]
+    pub bone_groups: Vec<Vrm0BoneGroup>,
+    #[serde(rename = "colliderGroups")]
+    pub collider_groups: Vec<Vrm0ColliderGroup>,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub(crate) struct Vrm0BoneGroup {
+    pub comment: Option<String>,
+    /// The key is misspelled in the VRM 0.x specification.
+    #[serde(rename = "stiffiness")]
+    pub stiffness: f32,
+    #[serde(rename = "gravityPower")]
+    pub gravity_power: f32,
+    /// The gravity direction in Unity's world coordinates.
+    #[serde(rename = "gravityDir")]
+    pub gravity_dir: Vrm0Vec3,
+    #[serde(rename = "dragForce")]
+    pub drag_force: f32,
+    /// The index of the center node, or `-1` if it is not set.
+    pub center: i64,
+    #[serde(rename = "hitRadius")]
+    pub hit_radius: f32,
+    /// The root nodes of the springs.
+    pub bones: Vec<usize>,
+    #[serde(rename = "colliderGroups")]
+    pub collider_groups: Vec<usize>,
+}
+
+impl Default for Vrm0BoneGroup {
+    fn default() -> Self {
+        Self {
+            comment: None,
+            stiffness: 1.,
+            gravity_power: 0.,
+            gravity_dir: Vrm0Vec3 {
+                x: 0.,
+                y: -1.,
+                z: 0.,
+            },
+            drag_force: 0.4,
+            center: -1,
+            hit_radius: 0.02,
+            bones: Vec::new(),
+            collider_groups: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0ColliderGroup {
+    pub node: usize,
+    pub colliders: Vec<Vrm0Collider>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct Vrm0Collider {
+    /// The offset from the node in Unity's local coordinates.
+    pub offset: Vrm0Vec3,
+    pub radius: f32,
+}
+
+impl Vrm0SecondaryAnimation {
+    pub fn to_vrmc_spring_bone(
+        &self,
+        document: &GltfDocument,
+    ) -> VRMCSpringBone {
+        let mut colliders = Vec::new();
+        let collider_groups = self
+            .collider_groups
+            .iter()
+            .enumerate()
+            .map(|(i, group)| {
+                let start = colliders.len() as u64;
+                colliders.extend(group.colliders.iter().map(|collider| Collider {
+                    node: group.node,
+                    shape: ColliderShape::Sphere(Sphere {
+                        offset: collider.offset.to_vrm1(),
+                        radius: collider.radius,
+                    }),
+                }));
+                ColliderGroup {
+                    name: format!("colliderGroup{i}"),
+                    colliders: (start..colliders.len() as u64).collect(),
+                }
+            })
+            .collect();
+        VRMCSpringBone {
+            spec_version: "1.0".to_string(),
+            colliders,
+            collider_groups,
+            springs: self
+                .bone_groups
+                .iter()
+                .flat_map(|group| group.to_springs(document))
+                .collect(),
+        }
+    }
+}
+
+impl Vrm0BoneGroup {
+    /// Expands each root bone into the chains of its descendants.
+    fn to_springs(
+        &self,
+        document: &GltfDocument,
+    ) -> Vec<Spring> {
+        let name = self.comment.clone().unwrap_or_default();
+        let mut chains = Vec::new();
+        for root in &self.bones {
+            collect_chains(document, *root, Vec::new(), &mut chains);
+        }
+        chains
+            .into_iter()
+            .map(|chain| Spring {
+                name: name.clone(),
+                joints: chain.into_iter().map(|node| self.joint(node)).collect(),
+                collider_groups: Some(self.collider_groups.clone()),
+                center: usize::try_from(self.center).ok(),
+            })
+            .collect()
+    }
+
+    fn joint(
+        &self,
+        node: usize,
+    ) -> SpringJoint {
+        SpringJoint {
+            node,
+            drag_force: Some(self.drag_force),
+            gravity_dir: Some(self.gravity_dir.to_vrm1()),
+            gravity_power: Some(self.gravity_power),
+            hit_radius: Some(self.hit_radius),
+            stiffness: Some(self.stiffness),
+        }
+    }
+}
+
+/// Walks down from the node, continuing the chain through the first child
+/// and starting a new chain from the node to each of the other children as `UniVRM` does,
+/// so that every segment below the root has a joint.
+///
+/// VRM 1.0 treats the last joint as the tail, so the leaves are given the virtual tails
+/// by [`append_virtual_tails`] beforehand; chains that still have a single joint are dropped.
+fn collect_chains(
+    document: &GltfDocument,
+    node: usize,
+    mut chain: Vec<usize>,
+    chains: &mut Vec<Vec<usize>>,
+) {
+    if chain.contains(&node) {
+        return;
+    }
+    chain.push(node);
+    let children = document
+        .nodes
+        .get(node)
+        .map(|node| node.children.as_slice())
+        .unwrap_or_default();
+    let Some((first, others)) = children.split_first() else {
+        if 1 < chain.len() {
+            chains.push(chain);
+        }
+        return;
+    };
+    for child in others {
+        collect_chains(document, *child, vec![node], chains);
+    }
+    collect_chains(document, *first, chain, chains);
+}
+
+/// Appends a child node to each leaf of the springs in the glTF JSON,
+/// [`VIRTUAL_TAIL_LENGTH`] away along the direction from the parent to the leaf.
+///
+/// VRM 0.x swings the leaves towards such a virtual tail, whereas VRM 1.0 needs an actual tail node.
+/// Leaves that lie on their parent have no direction and are left as they are.
+pub(crate) fn append_virtual_tails(root: &mut Value) {
+    let Some(nodes) = root.get("nodes").and_then(Value::as_array) else {
+        return;
+    };
+    let children = |node: usize| {
+        nodes
+            .get(node)
+            .and_then(|node| node.get("children"))
+            .and_then(Value::as_array)
+            .map(|children| {
+                children
+                    .iter()
+                    .filter_map(|child| usize::try_from(child.as_u64()?).ok())
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    let mut visited = HashSet::new();
+    let mut stack = root
+        .pointer("/extensions/VRM/secondaryAnimation/boneGroups")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|group| group.get("bones").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|bone| usize::try_from(bone.as_u64()?).ok())
+        .collect::<Vec<_>>();
+    let mut leaves = Vec::new();
+    while let Some(node) = stack.pop() {
+        if node < nodes.len() && visited.insert(node) {
+            let node_children = children(node);
+            if node_children.is_empty() {
+                leaves.push(node);
+            }
+            stack.extend(node_children);
+        }
+    }
+    leaves.sort_unstable();
+
+    let tails = leaves
+        .into_iter()
+        .filter_map(|leaf| {
+            let node = &nodes[leaf];
+            let (scale, rotation, translation) = local_transform(node);
+            let direction = translation.try_normalize()?;
+            let tail = rotation.inverse() * direction * VIRTUAL_TAIL_LENGTH / scale;
+            let name = node
+                .get("name")
+                .and_then(Value::as_str)
+                .map(ToString::to_string)
+                .unwrap_or_else(|| format!("GltfNode{leaf}"));
+            Some((
+                leaf,
+                serde_json::json!({
+                    "name": format!("{name}_end"),
+                    "translation": tail.to_array(),
+                }),
+            ))
+        })
+        .collect::<Vec<_>>();
+    let Some(nodes) = root.get_mut("nodes").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for (leaf, tail) in tails {
+        let tail_index = nodes.len();
+        nodes.push(tail);
+        if let Some(leaf) = nodes[leaf].as_object_mut() {
+            leaf.insert("children".to_string(), Value::from(vec![tail_index]));
+        }
+    }
+}
+
+fn local_transform(node: &Value) -> (Vec3, Quat, Vec3) {
+    let floats = |key: &str| {
+        node.get(key).and_then(Value::as_array).map(|values| {
+            values
+                .iter()
+                .map(|value| value.as_f64().unwrap_or_default() as f32)
+                .collect::<Vec<_>>()
+        })
+    };
+    if let Some(matrix) = floats("matrix").filter(|matrix| matrix.len() == 16) {
+        return Mat4::from_cols_slice(&matrix).to_scale_rotation_translation();
+    }
+    let vec3 = |key: &str, default: Vec3| {
+        floats(key)
+            .filter(|values| values.len() == 3)
+            .map_or(default, |values| Vec3::from_slice(&values))
+    };
+    let rotation = floats("rotation")
+        .filter(|values| values.len() == 4)
+        .map_or(Quat::IDENTITY, |values| {
+            Quat::from_slice(&values).normalize()
+        });
+    (
+        vec3("scale", Vec3::ONE),
+        rotation,
+        vec3("translation", Vec3::ZERO),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::vrm0::secondary_animation::{
+        append_virtual_tails, Vrm0SecondaryAnimation,
+    };
+    use crate::vrm::gltf::extensions::vrm0::{GltfDocument, GltfDocumentNode};
+    use crate::vrm::gltf::extensions::vrmc_spring_bone::ColliderShape;
+
+    fn node(children: Vec<usize>) -> GltfDocumentNode {
+        GltfDocumentNode {
+            mesh: None,
+            children,
+        }
+    }
+
+    #[test]
+    fn expand_roots_into_chains() -> TestResult {
+        // 0 ─┬─ 1 ── 2
+        //    └─ 3 ── 4
+        let document = GltfDocument {
+            nodes: vec![
+                node(vec![1, 3]),
+                node(vec![2]),
+                node(vec![]),
+                node(vec![4]),
+                node(vec![]),
+            ],
+            ..Default::default()
+        };
+        let secondary: Vrm0SecondaryAnimation = serde_json::from_str(
+            r#"{
+                "boneGroups": [{
+                    "comment": "hair",
+                    "stiffiness": 0.5,
+                    "gravityPower": 1,
+                    "gravityDir": { "x": 1, "y": -1, "z": 0 },
+                    "dragForce": 0.2,
+                    "center": -1,
+                    "hitRadius": 0.03,
+                    "bones": [0],
+                    "colliderGroups": [1]
+                }],
+                "colliderGroups": [
+                    { "node": 2, "colliders": [{ "offset": { "x": 0, "y": 0, "z": 1 }, "radius": 0.1 }] },
+                    { "node": 4, "colliders": [
+                        { "offset": { "x": 0, "y": 0, "z": 0 }, "radius": 0.1 },
+                        { "offset": { "x": 0, "y": 0, "z": 0 }, "radius": 0.2 }
+                    ] }
+                ]
+            }"#,
+        )?;
+        let spring_bone = secondary.to_vrmc_spring_bone(&document);
+
+        let chains: Vec<Vec<usize>> = spring_bone
+            .springs
+            .iter()
+            .map(|spring| spring.joints.iter().map(|joint| joint.node).collect())
+            .collect();
+        assert_eq!(chains, vec![vec![0, 3, 4], vec![0, 1, 2]]);
+
+        let spring = &spring_bone.springs[0];
+        assert_eq!(spring.name, "hair");
+        assert_eq!(spring.center, None);
+        assert_eq!(spring.joints[0].stiffness, Some(0.5));
+        assert_eq!(spring.joints[0].gravity_dir, Some([-1., -1., 0.]));
+
+        assert_eq!(spring_bone.collider_groups[1].colliders, vec![1, 2]);
+        assert_eq!(spring_bone.spring_colliders(&[1]).len(), 2);
+        let ColliderShape::Sphere(sphere) = spring_bone.colliders[0].shape else {
+            panic!("expected a sphere collider");
+        };
+        assert_eq!(sphere.offset, [0., 0., 1.]);
+        success!()
+    }
+
+    #[test]
+    fn append_tails_to_spring_leaves() -> TestResult {
+        // 0 ─┬─ 1
+        //    └─ 2    3
+        let mut root = serde_json::json!({
+            "extensions": { "VRM": { "secondaryAnimation": { "boneGroups": [{ "bones": [0, 3] }] } } },
+            "nodes": [
+                { "name": "hair", "children": [1, 2] },
+                { "name": "hair_a", "translation": [0.0, -0.1, 0.0], "rotation": [0.0, 0.0, 1.0, 0.0] },
+                { "translation": [0.0, 0.0, 0.0] },
+                { "name": "ribbon", "translation": [0.0, 0.0, 0.2] }
+            ]
+        });
+        append_virtual_tails(&mut root);
+
+        let nodes = root["nodes"].as_array().unwrap();
+        assert_eq!(nodes.len(), 6);
+        assert_eq!(root["nodes"][1]["children"], serde_json::json!([4]));
+        assert_eq!(root["nodes"][4]["name"], "hair_a_end");
+        let tail = root["nodes"][4]["translation"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_f64().unwrap() as f32)
+            .collect::<Vec<_>>();
+        assert!((tail[1] - 0.07).abs() < 1e-6);
+        assert!(root["nodes"][2].get("children").is_none());
+        assert_eq!(root["nodes"][3]["children"], serde_json::json!([5]));
+        assert_eq!(root["nodes"][5]["name"], "ribbon_end");
+        success!()
+    }
+}
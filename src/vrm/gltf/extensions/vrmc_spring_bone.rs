@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct VRMCSpringBone {
     /// Represents the specification version of the `VRMC_springBone` extension.
     #[serde(rename = "specVersion")]
@@ -32,13 +32,14 @@ impl VRMCSpringBone {
     ) -> Vec<Collider> {
         collider_group_indices
             .iter()
-            .flat_map(|index| self.collider_groups[*index].colliders.clone())
-            .flat_map(|index| self.colliders.get(index as usize).cloned())
+            .filter_map(|index| self.collider_groups.get(*index))
+            .flat_map(|group| group.colliders.iter())
+            .filter_map(|index| self.colliders.get(usize::try_from(*index).ok()?).copied())
             .collect()
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct ColliderGroup {
     /// Group name
     pub name: String,
@@ -56,7 +57,7 @@ pub struct Collider {
     pub shape: ColliderShape,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Spring {
     /// Spring name
     pub name: String,
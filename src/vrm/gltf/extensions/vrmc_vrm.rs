@@ -3,11 +3,11 @@ use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct VrmcVrm {
     pub expressions: Option<Expressions>,
-    // #[serde(rename = "firstPerson")]
-    // pub first_person: Option<FirstPerson>,
+    #[serde(rename = "firstPerson")]
+    pub first_person: Option<FirstPerson>,
     pub humanoid: Humanoid,
     #[serde(rename = "lookAt")]
     pub look_at: Option<LookAtProperties>,
@@ -16,53 +16,175 @@ pub struct VrmcVrm {
     pub spec_version: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug, Default)]
 pub struct Expressions {
+    #[serde(default)]
     pub preset: HashMap<String, VrmPreset>,
+    /// The expressions defined by the author such as `blush`.
+    #[serde(default)]
+    pub custom: HashMap<String, VrmPreset>,
+}
+
+impl Expressions {
+    /// Returns both the preset and the custom expressions.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &VrmPreset)> {
+        self.preset.iter().chain(self.custom.iter())
+    }
+
+    /// Returns the number of the preset and the custom expressions.
+    pub fn len(&self) -> usize {
+        self.preset.len() + self.custom.len()
+    }
+
+    /// Returns `true` if there are no expressions.
+    pub fn is_empty(&self) -> bool {
+        self.preset.is_empty() && self.custom.is_empty()
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+/// An expression of `VRMC_vrm`, used for both the preset and the custom expressions.
+#[derive(Serialize, Deserialize, Debug)]
 pub struct VrmPreset {
     /// If this value is `true`, `weight` value greater than 0.5 is 1.0, otherwise 0.0.
-    #[serde(rename = "isBinary")]
+    #[serde(rename = "isBinary", default)]
     pub is_binary: bool,
     #[serde(rename = "morphTargetBinds")]
     pub morph_target_binds: Option<Vec<MorphTargetBind>>,
-    #[serde(rename = "overrideBlink")]
-    pub override_blink: String,
-    #[serde(rename = "overrideLookAt")]
-    pub override_look_at: String,
-    #[serde(rename = "overrideMouth")]
-    pub override_mouth: String,
+    #[serde(rename = "materialColorBinds")]
+    pub material_color_binds: Option<Vec<MaterialColorBind>>,
+    #[serde(rename = "textureTransformBinds")]
+    pub texture_transform_binds: Option<Vec<TextureTransformBind>>,
+    /// How this expression overrides `blink`, `blinkLeft` and `blinkRight`.
+    #[serde(rename = "overrideBlink", default)]
+    pub override_blink: ExpressionOverrideType,
+    /// How this expression overrides `lookUp`, `lookDown`, `lookLeft` and `lookRight`.
+    #[serde(rename = "overrideLookAt", default)]
+    pub override_look_at: ExpressionOverrideType,
+    /// How this expression overrides `aa`, `ih`, `ou`, `ee` and `oh`.
+    #[serde(rename = "overrideMouth", default)]
+    pub override_mouth: ExpressionOverrideType,
+}
+
+/// How an expression suppresses the other expression groups while it is active.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Default, PartialEq, Eq, Reflect)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+#[serde(rename_all = "snake_case")]
+pub enum ExpressionOverrideType {
+    /// The group is not affected.
+    #[default]
+    None,
+    /// The group is disabled while the weight of the expression is greater than 0.
+    Block,
+    /// The weights of the group are multiplied by `1 - weight`.
+    Blend,
 }
 
-#[derive(Serialize, Deserialize)]
+impl ExpressionOverrideType {
+    /// Returns the rate to suppress the overridden group when the expression has the `weight`.
+    pub fn rate(
+        self,
+        weight: f32,
+    ) -> f32 {
+        match self {
+            Self::Block if 0.0 < weight => 1.0,
+            Self::None | Self::Block => 0.0,
+            Self::Blend => weight,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct MorphTargetBind {
     pub index: usize,
     pub node: usize,
     pub weight: f32,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct MaterialColorBind {
+    /// The index of the material.
+    pub material: usize,
+    #[serde(rename = "type")]
+    pub r#type: MaterialColorType,
+    /// The color in linear space when the weight of the expression is `1.0`.
+    #[serde(rename = "targetValue")]
+    pub target_value: [f32; 4],
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TextureTransformBind {
+    /// The index of the material.
+    pub material: usize,
+    /// The UV scale when the weight of the expression is `1.0`.
+    #[serde(default = "TextureTransformBind::default_scale")]
+    pub scale: [f32; 2],
+    /// The UV offset when the weight of the expression is `1.0`.
+    #[serde(default)]
+    pub offset: [f32; 2],
+}
+
+impl TextureTransformBind {
+    const fn default_scale() -> [f32; 2] {
+        [1.0, 1.0]
+    }
+}
+
+/// The material property that [`MaterialColorBind`] changes.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Hash, Reflect)]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+#[serde(rename_all = "camelCase")]
+pub enum MaterialColorType {
+    Color,
+    EmissionColor,
+    ShadeColor,
+    MatcapColor,
+    RimColor,
+    OutlineColor,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct Humanoid {
     #[serde(rename = "humanBones")]
     pub human_bones: HashMap<String, VrmNode>,
 }
 
-#[derive(Serialize, Deserialize)]
+impl Humanoid {
+    /// The bones that every VRM must have.
+    ///
+    /// Please see [`humanoid`](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_vrm-1.0/humanoid.md) for details.
+    pub const REQUIRED_BONES: [&'static str; 15] = [
+        "hips",
+        "spine",
+        "head",
+        "leftUpperLeg",
+        "leftLowerLeg",
+        "leftFoot",
+        "rightUpperLeg",
+        "rightLowerLeg",
+        "rightFoot",
+        "leftUpperArm",
+        "leftLowerArm",
+        "leftHand",
+        "rightUpperArm",
+        "rightLowerArm",
+        "rightHand",
+    ];
+}
+
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Struct6 {
     pub node: i64,
     #[serde(rename = "type")]
     pub r#type: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct FirstPerson {
-    #[serde(rename = "meshAnnotations")]
+    #[serde(rename = "meshAnnotations", default)]
     pub mesh_annotations: Vec<Struct6>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Struct5 {
     #[serde(rename = "isBinary")]
     pub is_binary: bool,
@@ -74,17 +196,17 @@ pub struct Struct5 {
     pub override_mouth: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub struct Meta {
-    #[serde(rename = "allowAntisocialOrHateUsage")]
+    #[serde(rename = "allowAntisocialOrHateUsage", default)]
     pub allow_antisocial_or_hate_usage: bool,
-    #[serde(rename = "allowExcessivelySexualUsage")]
+    #[serde(rename = "allowExcessivelySexualUsage", default)]
     pub allow_excessively_sexual_usage: bool,
-    #[serde(rename = "allowExcessivelyViolentUsage")]
+    #[serde(rename = "allowExcessivelyViolentUsage", default)]
     pub allow_excessively_violent_usage: bool,
-    #[serde(rename = "allowPoliticalOrReligiousUsage")]
+    #[serde(rename = "allowPoliticalOrReligiousUsage", default)]
     pub allow_political_or_religious_usage: bool,
-    #[serde(rename = "allowRedistribution")]
+    #[serde(rename = "allowRedistribution", default)]
     pub allow_redistribution: bool,
     pub authors: Vec<String>,
     #[serde(rename = "avatarPermission")]
@@ -137,6 +259,19 @@ pub struct RangeMap {
     pub output_scale: f32,
 }
 
+impl RangeMap {
+    /// Maps the absolute value of `input` to the output, clamping it at `input_max_value`.
+    pub(crate) fn map(
+        &self,
+        input: f32,
+    ) -> f32 {
+        if self.input_max_value <= 0.0 {
+            return 0.0;
+        }
+        input.abs().min(self.input_max_value) / self.input_max_value * self.output_scale
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Reflect)]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 #[serde(rename_all = "snake_case")]
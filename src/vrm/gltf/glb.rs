@@ -0,0 +1,169 @@
+//! This module reads and writes the chunks of GLB, the binary container of glTF.
+
+use crate::error::VrmError;
+use bevy::asset::io::{AsyncSeekForwardExt, Reader};
+use bevy::tasks::futures_lite::AsyncReadExt;
+
+pub(crate) const GLB_MAGIC: &[u8; 4] = b"glTF";
+pub(crate) const GLB_HEADER_LENGTH: usize = 12;
+pub(crate) const GLB_CHUNK_JSON: u32 = 0x4E4F_534A;
+pub(crate) const GLB_CHUNK_BIN: u32 = 0x004E_4942;
+
+/// Splits GLB into the JSON chunk and the binary chunk.
+///
+/// If the bytes are not GLB, they are treated as the glTF JSON.
+pub(crate) fn split_glb(bytes: &[u8]) -> Result<(&[u8], Option<&[u8]>), VrmError> {
+    if !bytes.starts_with(GLB_MAGIC) {
+        return Ok((bytes, None));
+    }
+    let mut json = None;
+    let mut bin = None;
+    let mut offset = GLB_HEADER_LENGTH;
+    while let Some(header) = bytes.get(offset..offset + 8) {
+        let length = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+        let chunk_type = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        let chunk = bytes
+            .get(offset + 8..offset + 8 + length)
+            .ok_or_else(|| VrmError::InvalidGltf("GLB chunk is out of range".to_string()))?;
+        match chunk_type {
+            GLB_CHUNK_JSON => json = Some(chunk),
+            GLB_CHUNK_BIN => bin = Some(chunk),
+            _ => {}
+        }
+        offset += 8 + length;
+    }
+    let json = json.ok_or_else(|| VrmError::InvalidGltf("Not found GLB JSON chunk".to_string()))?;
+    Ok((json, bin))
+}
+
+/// Writes GLB from the JSON chunk and the binary chunk.
+pub(crate) fn write_glb(
+    json: &[u8],
+    bin: Option<&[u8]>,
+) -> Vec<u8> {
+    // Chunks are aligned to 4 bytes; JSON is padded with spaces and the binary with zeros.
+    let padded = |len: usize| len.div_ceil(4) * 4;
+    let length = GLB_HEADER_LENGTH
+        + 8
+        + padded(json.len())
+        + bin.map(|bin| 8 + padded(bin.len())).unwrap_or_default();
+    let mut glb = Vec::with_capacity(length);
+    glb.extend(GLB_MAGIC);
+    glb.extend(2u32.to_le_bytes());
+    glb.extend((length as u32).to_le_bytes());
+    let mut write_chunk = |chunk_type: u32, data: &[u8], padding: u8| {
+        glb.extend((padded(data.len()) as u32).to_le_bytes());
+        glb.extend(chunk_type.to_le_bytes());
+        glb.extend(data);
+        glb.resize(glb.len() + padded(data.len()) - data.len(), padding);
+    };
+    write_chunk(GLB_CHUNK_JSON, json, b' ');
+    if let Some(bin) = bin {
+        write_chunk(GLB_CHUNK_BIN, bin, 0);
+    }
+    glb
+}
+
+/// Reads the glTF JSON from the start of GLB, leaving the reader at the chunk next to it.
+///
+/// If the bytes are not GLB, they are read to the end and treated as the glTF JSON.
+/// Returns the JSON and whether the file is GLB.
+pub(crate) async fn read_glb_json(reader: &mut dyn Reader) -> Result<(Vec<u8>, bool), VrmError> {
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic).await?;
+    if &magic != GLB_MAGIC {
+        let mut json = magic.to_vec();
+        reader.read_to_end(&mut json).await?;
+        return Ok((json, false));
+    }
+    let mut header = [0; GLB_HEADER_LENGTH - 4];
+    reader.read_exact(&mut header).await?;
+    let (length, chunk_type) = read_chunk_header(reader).await?;
+    if chunk_type != GLB_CHUNK_JSON {
+        return Err(VrmError::InvalidGltf(
+            "Not found GLB JSON chunk".to_string(),
+        ));
+    }
+    let mut json = vec![0; length];
+    reader.read_exact(&mut json).await?;
+    Ok((json, true))
+}
+
+/// Reads the range of the binary chunk that follows the JSON chunk read by [`read_glb_json`],
+/// skipping the bytes before it.
+///
+/// Returns `None` if there is no binary chunk or the range is out of it.
+pub(crate) async fn read_glb_bin_range(
+    reader: &mut dyn Reader,
+    offset: usize,
+    length: usize,
+) -> Result<Option<Vec<u8>>, VrmError> {
+    // The binary chunk is optional, so the end of the file is not an error here.
+    let Ok((chunk_length, chunk_type)) = read_chunk_header(reader).await else {
+        return Ok(None);
+    };
+    if chunk_type != GLB_CHUNK_BIN
+        || offset
+            .checked_add(length)
+            .is_none_or(|end| chunk_length < end)
+    {
+        return Ok(None);
+    }
+    reader.seek_forward(offset as u64).await?;
+    let mut bytes = vec![0; length];
+    reader.read_exact(&mut bytes).await?;
+    Ok(Some(bytes))
+}
+
+async fn read_chunk_header(reader: &mut dyn Reader) -> Result<(usize, u32), VrmError> {
+    let mut header = [0; 8];
+    reader.read_exact(&mut header).await?;
+    Ok((
+        u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize,
+        u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::glb::{read_glb_bin_range, read_glb_json, split_glb, write_glb};
+    use bevy::asset::io::SliceReader;
+    use bevy::tasks::block_on;
+
+    #[test]
+    fn write_and_split_glb() -> TestResult {
+        let glb = write_glb(br#"{"a":1}"#, Some(&[1, 2, 3]));
+        assert_eq!(glb.len() % 4, 0);
+        let (json, bin) = split_glb(&glb)?;
+        assert_eq!(json, br#"{"a":1} "#);
+        assert_eq!(bin, Some(&[1u8, 2, 3, 0][..]));
+        success!()
+    }
+
+    #[test]
+    fn read_json_and_range_of_bin() -> TestResult {
+        let glb = write_glb(br#"{"a":1}"#, Some(&[0, 1, 2, 3, 4, 5]));
+        let mut reader = SliceReader::new(&glb);
+        let (json, is_glb) = block_on(read_glb_json(&mut reader))?;
+        assert!(is_glb);
+        assert_eq!(json, br#"{"a":1} "#);
+        assert_eq!(
+            block_on(read_glb_bin_range(&mut reader, 2, 3))?,
+            Some(vec![2, 3, 4])
+        );
+        success!()
+    }
+
+    #[test]
+    fn read_gltf_json_to_end() -> TestResult {
+        let json = br#"{"asset":{"version":"2.0"}}"#;
+        let mut reader = SliceReader::new(json);
+        let (read, is_glb) = block_on(read_glb_json(&mut reader))?;
+        assert!(!is_glb);
+        assert_eq!(read, json);
+        assert_eq!(block_on(read_glb_bin_range(&mut reader, 0, 1))?, None);
+        success!()
+    }
+}
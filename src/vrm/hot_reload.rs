@@ -0,0 +1,269 @@
+//! This module reloads VRM onto the existing entities when the asset is modified.
+//!
+//! The scene and the registries are rebuilt from the new [`VrmAsset`],
+//! while the entity, [`LookAt`](crate::prelude::LookAt), [`VrmExpressionWeights`](crate::prelude::VrmExpressionWeights) and the playing VRMA are kept.
+
+use crate::prelude::*;
+use crate::vrm::initialize::{request_initialize, spawn_vrm};
+use crate::vrm::loader::VrmAsset;
+use crate::vrm::spring_bone::registry::{
+    SpringColliderRegistry, SpringJointPropsRegistry, SpringNodeRegistry,
+};
+use crate::vrm::Initialized;
+use crate::vrma::VrmAnimationNodeIndex;
+use bevy::animation::{ActiveAnimation, RepeatAnimation};
+use bevy::app::{App, Plugin, Update};
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy::scene::{SceneInstance, SceneSpawner};
+use std::time::Duration;
+
+pub(super) struct VrmHotReloadPlugin;
+
+impl Plugin for VrmHotReloadPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.add_systems(
+            Update,
+            (
+                reload_modified_vrms.before(spawn_vrm),
+                restore_reloaded_vrms.after(request_initialize),
+            ),
+        );
+    }
+}
+
+/// Holds the asset that the VRM was spawned from, so that it can be reloaded when the file is modified.
+#[derive(Component, Debug)]
+pub(crate) struct VrmSource(pub(crate) Handle<VrmAsset>);
+
+/// The state of the VRM before reloading, which is restored after the new scene is initialized.
+#[derive(Component, Debug)]
+struct ReloadingVrm {
+    animation: Option<PlayingVrma>,
+}
+
+#[derive(Debug)]
+struct PlayingVrma {
+    vrma: Entity,
+    seek_time: f32,
+    repeat: RepeatAnimation,
+    speed: f32,
+    paused: bool,
+}
+
+impl PlayingVrma {
+    fn resume(
+        &self,
+        animation: &mut ActiveAnimation,
+    ) {
+        animation
+            .set_repeat(self.repeat)
+            .set_speed(self.speed)
+            .seek_to(self.seek_time);
+        if self.paused {
+            animation.pause();
+        }
+    }
+}
+
+fn reload_modified_vrms(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<VrmAsset>>,
+    mut scene_spawner: ResMut<SceneSpawner>,
+    vrms: Query<(
+        Entity,
+        &VrmSource,
+        Option<&SceneInstance>,
+        Option<&Children>,
+    )>,
+    vrmas: Query<&VrmAnimationNodeIndex, With<Vrma>>,
+    players: Query<(&AnimationPlayer, &AnimationTransitions)>,
+    searcher: ChildSearcher,
+) {
+    let modified = events
+        .read()
+        .filter_map(|event| match event {
+            AssetEvent::Modified { id } => Some(*id),
+            _ => None,
+        })
+        .collect::<HashSet<_>>();
+    if modified.is_empty() {
+        return;
+    }
+    for (vrm, source, instance, children) in vrms.iter() {
+        if !modified.contains(&source.0.id()) {
+            continue;
+        }
+        let state = ReloadingVrm {
+            animation: children.and_then(|children| {
+                obtain_playing_vrma(vrm, children, &searcher, &vrmas, &players)
+            }),
+        };
+        if let Some(expressions_root) = searcher.find_expressions_root(vrm) {
+            commands.entity(expressions_root).despawn();
+        }
+        if let Some(instance) = instance {
+            // The VRMA entities are also children of the VRM, so only the roots of the scene are despawned.
+            let scene_roots = scene_spawner
+                .iter_instance_entities(**instance)
+                .filter(|entity| children.is_some_and(|children| children.contains(entity)))
+                .collect::<Vec<_>>();
+            for entity in scene_roots {
+                commands.entity(entity).despawn();
+            }
+            scene_spawner.unregister_instance(**instance);
+        }
+        commands
+            .entity(vrm)
+            .remove::<(
+                Initialized,
+                SceneInstance,
+                VrmcMaterialRegistry,
+                (
+                    SpringJointPropsRegistry,
+                    SpringColliderRegistry,
+                    SpringNodeRegistry,
+                ),
+                LookAtProperties,
+                Vrm0,
+                VrmUsageViolations,
+            )>()
+            .insert((state, VrmHandle(source.0.clone())));
+    }
+}
+
+fn obtain_playing_vrma(
+    vrm: Entity,
+    children: &Children,
+    searcher: &ChildSearcher,
+    vrmas: &Query<&VrmAnimationNodeIndex, With<Vrma>>,
+    players: &Query<(&AnimationPlayer, &AnimationTransitions)>,
+) -> Option<PlayingVrma> {
+    let (player, transitions) = players.get(searcher.find_root_bone(vrm)?).ok()?;
+    let node_index = transitions.get_main_animation()?;
+    let animation = player.animation(node_index)?;
+    if animation.is_finished() {
+        return None;
+    }
+    let vrma = children
+        .iter()
+        .find(|child| vrmas.get(*child).is_ok_and(|index| index.0 == node_index))?;
+    Some(PlayingVrma {
+        vrma,
+        seek_time: animation.seek_time(),
+        repeat: animation.repeat_mode(),
+        speed: animation.speed(),
+        paused: animation.is_paused(),
+    })
+}
+
+fn restore_reloaded_vrms(
+    mut commands: Commands,
+    vrms: Query<(Entity, &ReloadingVrm), With<Initialized>>,
+    vrmas: Query<&VrmAnimationNodeIndex>,
+    mut players: Query<
+        (&mut AnimationPlayer, Option<&mut AnimationTransitions>),
+        With<AnimationGraphHandle>,
+    >,
+    childrens: Query<&Children>,
+    searcher: ChildSearcher,
+) {
+    for (vrm, state) in vrms.iter() {
+        let Some(expressions_root) = searcher.find_expressions_root(vrm) else {
+            continue;
+        };
+        let expression_entities = childrens
+            .get(expressions_root)
+            .map(|children| children.to_vec())
+            .unwrap_or_default();
+        // If the VRMA has been despawned during the reload, there is nothing to resume.
+        if let Some((playing, node_index)) = state
+            .animation
+            .as_ref()
+            .and_then(|playing| Some((playing, vrmas.get(playing.vrma).ok()?)))
+        {
+            let Some(root_bone) = searcher.find_root_bone(vrm) else {
+                continue;
+            };
+            // Wait until the animation graph is rebuilt for the new scene.
+            if !players.contains(root_bone) {
+                continue;
+            }
+            if let Ok((mut player, Some(mut transitions))) = players.get_mut(root_bone) {
+                playing.resume(transitions.play(&mut player, node_index.0, Duration::ZERO));
+            }
+            for expression in expression_entities.iter() {
+                if let Ok((mut player, _)) = players.get_mut(*expression) {
+                    player.stop_all();
+                    playing.resume(player.play(node_index.0));
+                }
+            }
+        }
+        commands.entity(vrm).remove::<ReloadingVrm>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::tests::test_app;
+    use crate::vrm::hot_reload::{restore_reloaded_vrms, PlayingVrma, ReloadingVrm};
+    use crate::vrm::Initialized;
+    use crate::vrma::VrmAnimationNodeIndex;
+    use bevy::animation::graph::AnimationNodeIndex;
+    use bevy::animation::RepeatAnimation;
+    use bevy::prelude::*;
+
+    fn spawn_reloading_vrm(
+        app: &mut App,
+        vrma: Entity,
+    ) -> Entity {
+        let vrm = app
+            .world_mut()
+            .spawn((
+                Initialized,
+                ReloadingVrm {
+                    animation: Some(PlayingVrma {
+                        vrma,
+                        seek_time: 0.5,
+                        repeat: RepeatAnimation::Forever,
+                        speed: 1.0,
+                        paused: false,
+                    }),
+                },
+            ))
+            .id();
+        app.world_mut()
+            .spawn((Name::new(Vrm::EXPRESSIONS_ROOT), ChildOf(vrm)));
+        app.world_mut()
+            .spawn((Name::new(Vrm::ROOT_BONE), ChildOf(vrm)));
+        vrm
+    }
+
+    #[test]
+    fn wait_for_animation_graph_of_playing_vrma() {
+        let mut app = test_app();
+        app.add_systems(Update, restore_reloaded_vrms);
+        let vrma = app
+            .world_mut()
+            .spawn(VrmAnimationNodeIndex(AnimationNodeIndex::new(1)))
+            .id();
+        let vrm = spawn_reloading_vrm(&mut app, vrma);
+        app.update();
+        assert!(app.world().get::<ReloadingVrm>(vrm).is_some());
+    }
+
+    #[test]
+    fn finish_reloading_without_despawned_vrma() {
+        let mut app = test_app();
+        app.add_systems(Update, restore_reloaded_vrms);
+        let vrma = app.world_mut().spawn_empty().id();
+        app.world_mut().despawn(vrma);
+        let vrm = spawn_reloading_vrm(&mut app, vrma);
+        app.update();
+        assert!(app.world().get::<ReloadingVrm>(vrm).is_none());
+    }
+}
@@ -16,34 +16,34 @@ use crate::vrm::{BoneRestGlobalTransform, BoneRestTransform, VrmBone};
 use crate::vrma::RetargetSource;
 use bevy::animation::{AnimationTarget, AnimationTargetId};
 use bevy::app::{App, Plugin};
-use bevy::asset::{Assets, Handle};
-use bevy::gltf::GltfNode;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 
 pub mod prelude {
-    pub use crate::vrm::humanoid_bone::bones::*;
+    pub use crate::vrm::humanoid_bone::{bones::*, HumanoidBoneRegistry};
 }
 
 #[derive(Event)]
 pub(crate) struct RequestInitializeHumanoidBones;
 
-#[derive(Component, Deref, Reflect, Default)]
-pub(crate) struct HumanoidBoneRegistry(HashMap<VrmBone, Name>);
+/// Holds the node name of each humanoid bone.
+///
+/// This is built when VRM(A) is loaded, and inserted into the VRM(A) entity.
+#[derive(Component, Deref, Reflect, Default, Debug, Clone)]
+#[reflect(Component)]
+pub struct HumanoidBoneRegistry(HashMap<VrmBone, Name>);
 
 impl HumanoidBoneRegistry {
-    pub fn new(
+    pub(crate) fn new(
         bones: &HashMap<String, VrmNode>,
-        node_assets: &Assets<GltfNode>,
-        nodes: &[Handle<GltfNode>],
+        node_names: &[Name],
     ) -> Self {
         Self(
             bones
                 .iter()
                 .filter_map(|(name, target_node)| {
-                    let node_handle = nodes.get(target_node.node)?;
-                    let node = node_assets.get(node_handle)?;
-                    Some((VrmBone(name.clone()), Name::new(node.name.clone())))
+                    let node_name = node_names.get(target_node.node)?;
+                    Some((VrmBone(name.clone()), node_name.clone()))
                 })
                 .collect(),
         )
@@ -101,8 +101,9 @@ fn apply_initialize_humanoid_bones(
     let Ok(registry) = models.get(model_entity) else {
         return;
     };
-    let Some(hips) =
-        searcher.find_from_name(model_entity, registry.get(&VrmBone::from("hips")).unwrap())
+    let Some(hips) = registry
+        .get(&VrmBone::from("hips"))
+        .and_then(|hips| searcher.find_from_name(model_entity, hips))
     else {
         return;
     };
@@ -121,6 +122,21 @@ fn apply_initialize_humanoid_bones(
                 player: *root_bone,
             },
         ));
+        if searcher
+            .find_from_name(*root_bone, Vrm::LOOK_AT_SOURCE)
+            .is_none()
+        {
+            commands.spawn((
+                Name::new(Vrm::LOOK_AT_SOURCE),
+                Transform::default(),
+                RetargetSource,
+                AnimationTarget {
+                    id: AnimationTargetId::from_name(&Name::new(Vrm::LOOK_AT_SOURCE)),
+                    player: *root_bone,
+                },
+                ChildOf(*root_bone),
+            ));
+        }
     }
 
     for (bone, name) in registry.iter() {
@@ -1,20 +1,18 @@
-use crate::error::vrm_error;
+use crate::error::{vrm_error, VrmError};
 use crate::prelude::ChildSearcher;
-use crate::vrm::expressions::{RequestInitializeExpressions, VrmExpressionRegistry};
-use crate::vrm::gltf::extensions::VrmExtensions;
+use crate::vrm::expressions::{RequestInitializeExpressions, VrmExpressionWeights};
+use crate::vrm::hot_reload::VrmSource;
 use crate::vrm::humanoid_bone::{HumanoidBoneRegistry, RequestInitializeHumanoidBones};
-use crate::vrm::loader::{VrmAsset, VrmHandle};
-use crate::vrm::mtoon::VrmcMaterialRegistry;
+use crate::vrm::loader::{VrmAsset, VrmHandle, VrmLoadFailed};
+use crate::vrm::meta::usage_policy::{VrmUsagePolicy, VrmUsagePolicyAction, VrmUsageViolations};
 use crate::vrm::spring_bone::initialize::RequestInitializeSpringBone;
-use crate::vrm::spring_bone::registry::*;
-use crate::vrm::{Initialized, Vrm, VrmPath};
+use crate::vrm::{Initialized, Vrm, Vrm0, VrmPath};
 use crate::vrma::animation::animation_graph::RequestUpdateAnimationGraph;
 use crate::vrma::Vrma;
 use bevy::app::{App, Update};
-use bevy::asset::Assets;
-use bevy::gltf::GltfNode;
+use bevy::asset::{Assets, LoadState};
 use bevy::prelude::*;
-use bevy::scene::SceneRoot;
+use bevy::scene::{SceneInstance, SceneRoot, SceneSpawner};
 
 pub(crate) struct VrmInitializePlugin;
 
@@ -23,69 +21,83 @@ impl Plugin for VrmInitializePlugin {
         &self,
         app: &mut App,
     ) {
-        app.add_systems(Update, (spawn_vrm, request_initialize));
+        app.add_systems(Update, (spawn_vrm, request_initialize).chain());
     }
 }
 
-fn spawn_vrm(
+pub(super) fn spawn_vrm(
     mut commands: Commands,
-    node_assets: Res<Assets<GltfNode>>,
+    asset_server: Res<AssetServer>,
     vrm_assets: Res<Assets<VrmAsset>>,
+    policy: Res<VrmUsagePolicy>,
     handles: Query<(Entity, &VrmHandle)>,
 ) {
     for (vrm_handle_entity, handle) in handles.iter() {
         let Some(vrm) = vrm_assets.get(handle.0.id()) else {
+            if let Some(LoadState::Failed(e)) = asset_server.get_load_state(handle.0.id()) {
+                trigger_load_failed(&mut commands, vrm_handle_entity, VrmError::Load(e));
+            }
             continue;
         };
-        commands.entity(vrm_handle_entity).remove::<VrmHandle>();
 
-        let Some(scene) = vrm.gltf.scenes.first() else {
+        let Some(scene) = vrm.gltf.scenes.get(vrm.settings.scene) else {
             continue;
         };
-        let extensions = match VrmExtensions::from_gltf(&vrm.gltf) {
-            Ok(extensions) => extensions,
-            Err(e) => {
-                vrm_error!("Failed to load VRM extensions", e);
-                continue;
-            }
-        };
+        let violations = policy.violations(&vrm.meta);
+        if !violations.is_empty() && policy.action == VrmUsagePolicyAction::Refuse {
+            trigger_load_failed(
+                &mut commands,
+                vrm_handle_entity,
+                VrmError::UsageNotPermitted(violations),
+            );
+            continue;
+        }
 
+        let extensions = &vrm.extensions;
         let mut cmd = commands.entity(vrm_handle_entity);
-        cmd.insert((
-            Vrm,
-            Name::new(extensions.name().unwrap_or_else(|| "VRM".to_string())),
-            SceneRoot(scene.clone()),
-            VrmcMaterialRegistry::new(&vrm.gltf, vrm.images.clone()),
-            VrmExpressionRegistry::new(&extensions, &node_assets, &vrm.gltf.nodes),
-            HumanoidBoneRegistry::new(
-                &extensions.vrmc_vrm.humanoid.human_bones,
-                &node_assets,
-                &vrm.gltf.nodes,
-            ),
-        ));
+        cmd.remove::<VrmHandle>()
+            .insert((
+                Vrm,
+                Name::new(extensions.name().unwrap_or_else(|| "VRM".to_string())),
+                SceneRoot(scene.clone()),
+                VrmSource(handle.0.clone()),
+                vrm.meta.clone(),
+                vrm.expressions.clone(),
+                vrm.humanoid_bones.clone(),
+            ))
+            .insert_if_new(VrmExpressionWeights::default());
+
+        if !violations.is_empty() {
+            cmd.insert(VrmUsageViolations(violations));
+        }
+
+        if let Some(materials) = vrm.materials.clone() {
+            cmd.insert(materials);
+        }
 
-        if let Some(spring_bone) = extensions.vrmc_spring_bone.as_ref() {
-            cmd.insert((
-                SpringJointPropsRegistry::new(
-                    &spring_bone.all_joints(),
-                    &node_assets,
-                    &vrm.gltf.nodes,
-                ),
-                SpringColliderRegistry::new(&spring_bone.colliders, &node_assets, &vrm.gltf.nodes),
-                SpringNodeRegistry::new(spring_bone, &node_assets, &vrm.gltf.nodes),
-            ));
+        if let Some(spring_bone) = vrm.spring_bone.clone() {
+            cmd.insert(spring_bone);
         }
 
-        if let Some(look_at) = extensions.vrmc_vrm.look_at.clone() {
+        if let Some(look_at) = extensions
+            .vrmc_vrm
+            .look_at
+            .clone()
+            .filter(|_| vrm.settings.look_at)
+        {
             cmd.insert(look_at);
         }
 
+        if extensions.is_vrm0() {
+            cmd.insert(Vrm0);
+        }
+
         if let Some(vrm_path) = handle.0.path() {
             #[cfg(feature = "develop")]
             {
                 if let Some(vrm_name) = vrm_path.path().file_stem() {
                     output_vrm_materials(vrm_name, &vrm.gltf);
-                    output_vrm_extensions(vrm_name, &extensions);
+                    output_vrm_extensions(vrm_name, extensions);
                 }
             }
             cmd.insert(VrmPath::new(vrm_path.path()));
@@ -93,14 +105,31 @@ fn spawn_vrm(
     }
 }
 
-fn request_initialize(
+fn trigger_load_failed(
+    commands: &mut Commands,
+    entity: Entity,
+    error: VrmError,
+) {
+    vrm_error!("Failed to spawn VRM", &error);
+    commands
+        .entity(entity)
+        .remove::<VrmHandle>()
+        .trigger(VrmLoadFailed { error });
+}
+
+pub(super) fn request_initialize(
     mut commands: Commands,
-    models: Query<(Entity, &HumanoidBoneRegistry, Has<Vrma>), Without<Initialized>>,
+    models: Query<(Entity, &HumanoidBoneRegistry, &SceneInstance, Has<Vrma>), Without<Initialized>>,
+    scene_spawner: Res<SceneSpawner>,
     parents: Query<&ChildOf>,
+    childrens: Query<&Children>,
+    vrmas: Query<(), (With<Vrma>, With<Initialized>)>,
     searcher: ChildSearcher,
 ) {
-    for (root, registry, has_vrma) in models.iter() {
-        if !searcher.has_been_spawned_all_bones(root, registry) {
+    for (root, registry, instance, has_vrma) in models.iter() {
+        if !scene_spawner.instance_is_ready(**instance)
+            || !searcher.has_been_spawned_all_bones(root, registry)
+        {
             continue;
         }
         commands
@@ -116,6 +145,17 @@ fn request_initialize(
             };
         } else {
             commands.entity(root).trigger(RequestInitializeExpressions);
+            // VRMAs that have already been initialized are retargeted to the reloaded scene.
+            for vrma in childrens
+                .get(root)
+                .into_iter()
+                .flat_map(|children| children.iter())
+                .filter(|child| vrmas.contains(*child))
+            {
+                commands
+                    .entity(vrma)
+                    .trigger(RequestUpdateAnimationGraph { vrma, vrm: root });
+            }
         }
         commands.entity(root).insert(Initialized);
     }
@@ -137,7 +177,7 @@ fn output_vrm_materials(
 #[cfg(feature = "develop")]
 fn output_vrm_extensions(
     vrm_name: &std::ffi::OsStr,
-    extensions: &VrmExtensions,
+    extensions: &crate::vrm::gltf::extensions::VrmExtensions,
 ) {
     let name = vrm_name.to_str().unwrap();
     std::fs::write(
@@ -0,0 +1,365 @@
+//! This module moves the mouth of VRM from a precomputed [`LipSyncTimeline`] or [`LipSyncTrack`].
+//!
+//! The viseme weights are added to `aa`, `ih`, `ou`, `ee` and `oh` through the expression pipeline,
+//! so `overrideMouth` of the other expressions is honoured.
+
+mod audio;
+mod track;
+
+use crate::system_set::VrmSystemSets;
+use crate::vrm::expressions::{resolve_expressions, VrmExpressionPreset};
+use bevy::app::{App, Plugin};
+use bevy::prelude::*;
+use std::time::Duration;
+
+pub use audio::LipSyncAnalyzer;
+pub use track::{phoneme_viseme, LipSyncTrack, TimedPhoneme};
+
+pub mod prelude {
+    pub use crate::vrm::lip_sync::{
+        phoneme_viseme, LipSync, LipSyncAnalyzer, LipSyncSource, LipSyncTimeline, LipSyncTrack,
+        TimedPhoneme, VisemeWeights,
+    };
+}
+
+/// The weights of the visemes of VRM.
+#[derive(Reflect, Debug, Default, Copy, Clone, PartialEq)]
+pub struct VisemeWeights {
+    pub aa: f32,
+    pub ih: f32,
+    pub ou: f32,
+    pub ee: f32,
+    pub oh: f32,
+}
+
+impl VisemeWeights {
+    /// Returns the weights paired with the expressions.
+    pub const fn expressions(&self) -> [(VrmExpressionPreset, f32); 5] {
+        [
+            (VrmExpressionPreset::Aa, self.aa),
+            (VrmExpressionPreset::Ih, self.ih),
+            (VrmExpressionPreset::Ou, self.ou),
+            (VrmExpressionPreset::Ee, self.ee),
+            (VrmExpressionPreset::Oh, self.oh),
+        ]
+    }
+
+    /// Linearly interpolates between `self` and `other`.
+    pub fn lerp(
+        &self,
+        other: &Self,
+        t: f32,
+    ) -> Self {
+        Self {
+            aa: self.aa.lerp(other.aa, t),
+            ih: self.ih.lerp(other.ih, t),
+            ou: self.ou.lerp(other.ou, t),
+            ee: self.ee.lerp(other.ee, t),
+            oh: self.oh.lerp(other.oh, t),
+        }
+    }
+
+    /// Returns the weights multiplied by `scale`.
+    pub fn scale(
+        &self,
+        scale: f32,
+    ) -> Self {
+        Self {
+            aa: self.aa * scale,
+            ih: self.ih * scale,
+            ou: self.ou * scale,
+            ee: self.ee * scale,
+            oh: self.oh * scale,
+        }
+    }
+}
+
+/// The viseme weights sampled at a fixed frame rate.
+///
+/// This is created by [`LipSyncAnalyzer`], and played by [`LipSync`].
+#[derive(Asset, Reflect, Debug, Clone, Default, PartialEq)]
+pub struct LipSyncTimeline {
+    /// The number of frames per second.
+    pub frame_rate: f32,
+    /// The viseme weights of each frame.
+    pub frames: Vec<VisemeWeights>,
+}
+
+impl LipSyncTimeline {
+    /// Returns the duration of the timeline.
+    pub fn duration(&self) -> Duration {
+        if self.frame_rate <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(self.frames.len() as f64 / f64::from(self.frame_rate))
+    }
+
+    /// Returns the weights at `time`, interpolating the adjacent frames.
+    ///
+    /// Returns all zeros outside the timeline.
+    pub fn sample(
+        &self,
+        time: Duration,
+    ) -> VisemeWeights {
+        let position = time.as_secs_f32() * self.frame_rate;
+        let index = position.floor() as usize;
+        let Some(current) = self.frames.get(index) else {
+            return VisemeWeights::default();
+        };
+        let next = self.frames.get(index + 1).copied().unwrap_or_default();
+        current.lerp(&next, position.fract())
+    }
+}
+
+/// The viseme source played by [`LipSync`].
+#[derive(Reflect, Debug, Clone, PartialEq)]
+pub enum LipSyncSource {
+    /// Frames analysed from the voice by [`LipSyncAnalyzer`].
+    Timeline(Handle<LipSyncTimeline>),
+    /// Timed phonemes such as the output of text-to-speech.
+    Track(Handle<LipSyncTrack>),
+}
+
+impl LipSyncSource {
+    fn duration(
+        &self,
+        timelines: &Assets<LipSyncTimeline>,
+        tracks: &Assets<LipSyncTrack>,
+    ) -> Option<Duration> {
+        match self {
+            Self::Timeline(handle) => timelines.get(handle).map(LipSyncTimeline::duration),
+            Self::Track(handle) => tracks.get(handle).map(LipSyncTrack::duration),
+        }
+    }
+
+    fn sample(
+        &self,
+        timelines: &Assets<LipSyncTimeline>,
+        tracks: &Assets<LipSyncTrack>,
+        time: Duration,
+    ) -> VisemeWeights {
+        match self {
+            Self::Timeline(handle) => timelines.get(handle).map(|t| t.sample(time)),
+            Self::Track(handle) => tracks.get(handle).map(|t| t.sample(time)),
+        }
+        .unwrap_or_default()
+    }
+}
+
+impl From<Handle<LipSyncTimeline>> for LipSyncSource {
+    fn from(handle: Handle<LipSyncTimeline>) -> Self {
+        Self::Timeline(handle)
+    }
+}
+
+impl From<Handle<LipSyncTrack>> for LipSyncSource {
+    fn from(handle: Handle<LipSyncTrack>) -> Self {
+        Self::Track(handle)
+    }
+}
+
+/// Plays [`LipSyncTimeline`] or [`LipSyncTrack`] on the VRM entity.
+///
+/// Insert this at the same time as playing the voice so that the mouth moves in sync with it.
+/// If the voice starts later, delay the playback with [`LipSync::with_start`].
+///
+/// [`LipSyncAnalyzer`] takes PCM samples decoded by the application, as below,
+/// or decodes Bevy's `AudioSource` with `LipSyncAnalyzer::analyze_audio_source` if the `bevy_audio` feature is enabled.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// /// The voice decoded by the application.
+/// #[derive(Resource)]
+/// struct DecodedVoice {
+///     samples: Vec<f32>,
+///     channels: u16,
+///     sample_rate: u32,
+/// }
+///
+/// fn speak(
+///     mut commands: Commands,
+///     mut timelines: ResMut<Assets<LipSyncTimeline>>,
+///     voice: Res<DecodedVoice>,
+///     vrms: Query<Entity, With<Vrm>>,
+/// ) {
+///     let timeline =
+///         LipSyncAnalyzer::default().analyze(&voice.samples, voice.channels, voice.sample_rate);
+///     for vrm in vrms.iter() {
+///         commands
+///             .entity(vrm)
+///             .insert(LipSync::new(timelines.add(timeline.clone())));
+///     }
+/// }
+///
+/// fn speak_text(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     vrms: Query<Entity, With<Vrm>>,
+/// ) {
+///     // The phonemes exported by the text-to-speech engine.
+///     let track: Handle<LipSyncTrack> = asset_server.load("voice/hello.lipsync.json");
+///     for vrm in vrms.iter() {
+///         commands
+///             .entity(vrm)
+///             .insert(LipSync::new(track.clone()).with_start(0.5));
+///     }
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone)]
+#[reflect(Component)]
+#[require(LipSyncState)]
+pub struct LipSync {
+    /// The source to play.
+    pub source: LipSyncSource,
+    /// The elapsed time of the playback; set this to seek.
+    pub elapsed: Duration,
+    /// The time to wait before the playback starts.
+    pub delay: Duration,
+    /// The playback speed.
+    ///
+    /// Negative and NaN values pause the playback.
+    pub speed: f32,
+    /// The scale of the weights.
+    pub weight: f32,
+    /// If `true`, the source is played from the start when it finishes.
+    pub repeat: bool,
+    /// If `true`, the playback is paused.
+    pub paused: bool,
+}
+
+impl LipSync {
+    pub fn new(source: impl Into<LipSyncSource>) -> Self {
+        Self {
+            source: source.into(),
+            elapsed: Duration::ZERO,
+            delay: Duration::ZERO,
+            speed: 1.0,
+            weight: 1.0,
+            repeat: false,
+            paused: false,
+        }
+    }
+
+    /// Starts the playback after `seconds`.
+    ///
+    /// Negative and NaN values start immediately, and infinity never starts.
+    pub fn with_start(
+        mut self,
+        seconds: f32,
+    ) -> Self {
+        self.delay = saturating_duration(seconds);
+        self
+    }
+}
+
+/// Converts seconds into [`Duration`], clamping them to the range that [`Duration`] can represent.
+fn saturating_duration(seconds: f32) -> Duration {
+    // `max` also replaces NaN with zero.
+    Duration::try_from_secs_f32(seconds.max(0.0)).unwrap_or(Duration::MAX)
+}
+
+/// The viseme weights of [`LipSync`] in the current frame.
+#[derive(Component, Reflect, Debug, Default, Clone, PartialEq, Deref)]
+#[reflect(Component)]
+pub(crate) struct LipSyncState(pub(crate) VisemeWeights);
+
+pub(super) struct LipSyncPlugin;
+
+impl Plugin for LipSyncPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_asset::<LipSyncTimeline>()
+            .init_asset::<LipSyncTrack>()
+            .register_asset_loader(track::LipSyncTrackLoader)
+            .register_type::<LipSync>()
+            .register_type::<LipSyncState>()
+            .add_systems(
+                PostUpdate,
+                play_lip_sync
+                    .in_set(VrmSystemSets::Expressions)
+                    .before(resolve_expressions),
+            )
+            .add_observer(reset_lip_sync);
+    }
+}
+
+fn play_lip_sync(
+    mut lip_syncs: Query<(&mut LipSync, &mut LipSyncState)>,
+    timelines: Res<Assets<LipSyncTimeline>>,
+    tracks: Res<Assets<LipSyncTrack>>,
+    time: Res<Time>,
+) {
+    for (mut lip_sync, mut state) in lip_syncs.iter_mut() {
+        let Some(duration) = lip_sync.source.duration(&timelines, &tracks) else {
+            continue;
+        };
+        if !lip_sync.paused {
+            let mut delta = saturating_duration(time.delta_secs() * lip_sync.speed);
+            if !lip_sync.delay.is_zero() {
+                let waited = delta.min(lip_sync.delay);
+                lip_sync.delay -= waited;
+                delta -= waited;
+            }
+            let elapsed = lip_sync.elapsed.saturating_add(delta);
+            lip_sync.elapsed = if lip_sync.repeat && !duration.is_zero() && duration <= elapsed {
+                Duration::from_secs_f32(elapsed.as_secs_f32() % duration.as_secs_f32())
+            } else {
+                elapsed
+            };
+        }
+        let weights = if lip_sync.delay.is_zero() {
+            lip_sync
+                .source
+                .sample(&timelines, &tracks, lip_sync.elapsed)
+        } else {
+            VisemeWeights::default()
+        };
+        state.set_if_neq(LipSyncState(weights.scale(lip_sync.weight)));
+    }
+}
+
+fn reset_lip_sync(
+    trigger: Trigger<OnRemove, LipSync>,
+    mut states: Query<&mut LipSyncState>,
+) {
+    if let Ok(mut state) = states.get_mut(trigger.target()) {
+        state.set_if_neq(LipSyncState::default());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::lip_sync::{saturating_duration, LipSyncTimeline, VisemeWeights};
+    use std::time::Duration;
+
+    #[test]
+    fn saturate_durations() {
+        assert_eq!(saturating_duration(0.5), Duration::from_millis(500));
+        assert_eq!(saturating_duration(-1.0), Duration::ZERO);
+        assert_eq!(saturating_duration(f32::NAN), Duration::ZERO);
+        assert_eq!(saturating_duration(f32::INFINITY), Duration::MAX);
+        assert_eq!(saturating_duration(f32::MAX), Duration::MAX);
+    }
+
+    #[test]
+    fn sample_timeline() {
+        let timeline = LipSyncTimeline {
+            frame_rate: 10.0,
+            frames: vec![
+                VisemeWeights::default(),
+                VisemeWeights {
+                    aa: 1.0,
+                    ..Default::default()
+                },
+            ],
+        };
+        assert_eq!(timeline.duration(), Duration::from_millis(200));
+        assert!((timeline.sample(Duration::from_millis(50)).aa - 0.5).abs() < 1e-4);
+        assert!((timeline.sample(Duration::from_millis(150)).aa - 0.5).abs() < 1e-4);
+        assert_eq!(timeline.sample(Duration::from_millis(250)).aa, 0.0);
+    }
+}
@@ -0,0 +1,261 @@
+//! This module analyses PCM samples into [`LipSyncTimeline`].
+//!
+//! Each frame estimates the loudness from the RMS energy, and the first two formants (F1, F2)
+//! from the peaks of the spectrum. The formants are compared with those of the vowels to weigh the visemes.
+
+use crate::vrm::lip_sync::{LipSyncTimeline, VisemeWeights};
+use bevy::prelude::*;
+use std::f32::consts::TAU;
+
+/// The typical formants (F1, F2) of the vowels in Hz, in the order of `aa`, `ih`, `ou`, `ee` and `oh`.
+const VOWEL_FORMANTS: [(f32, f32); 5] = [
+    (800.0, 1200.0),
+    (300.0, 2300.0),
+    (350.0, 1400.0),
+    (500.0, 1900.0),
+    (500.0, 900.0),
+];
+
+const MIN_FREQUENCY: f32 = 150.0;
+const MAX_FREQUENCY: f32 = 3500.0;
+const FREQUENCY_STEP: f32 = 50.0;
+
+/// Analyses decoded PCM samples into [`LipSyncTimeline`].
+///
+/// The analysis runs offline, so the timeline can be computed once when the voice is loaded.
+/// The samples are taken from a raw buffer, or from Bevy's `AudioSource` with the `bevy_audio` feature.
+#[derive(Reflect, Debug, Clone)]
+pub struct LipSyncAnalyzer {
+    /// The number of frames per second of the timeline.
+    pub frame_rate: f32,
+    /// The length of the window analysed for each frame, in seconds.
+    pub window: f32,
+    /// The volume in decibels treated as silence.
+    pub min_volume_db: f32,
+    /// The volume in decibels treated as the mouth being fully open.
+    pub max_volume_db: f32,
+    /// How much the previous frame is kept, in the range of `0.0` to `1.0`.
+    pub smoothing: f32,
+    /// How strictly the formants are matched to the vowels; smaller values blend more visemes.
+    pub sharpness: f32,
+}
+
+impl Default for LipSyncAnalyzer {
+    fn default() -> Self {
+        Self {
+            frame_rate: 60.0,
+            window: 0.04,
+            min_volume_db: -50.0,
+            max_volume_db: -15.0,
+            smoothing: 0.4,
+            sharpness: 1.0,
+        }
+    }
+}
+
+impl LipSyncAnalyzer {
+    /// Analyses interleaved samples in the range of `-1.0` to `1.0`.
+    pub fn analyze(
+        &self,
+        samples: &[f32],
+        channels: u16,
+        sample_rate: u32,
+    ) -> LipSyncTimeline {
+        let channels = usize::from(channels.max(1));
+        let mono = samples
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect::<Vec<_>>();
+        self.analyze_mono(&mono, sample_rate)
+    }
+
+    /// Analyses interleaved 16-bit samples.
+    pub fn analyze_i16(
+        &self,
+        samples: &[i16],
+        channels: u16,
+        sample_rate: u32,
+    ) -> LipSyncTimeline {
+        let samples = samples
+            .iter()
+            .map(|sample| f32::from(*sample) / f32::from(i16::MAX))
+            .collect::<Vec<_>>();
+        self.analyze(&samples, channels, sample_rate)
+    }
+
+    /// Decodes [`AudioSource`](bevy::audio::AudioSource) and analyses its samples.
+    ///
+    /// The formats that can be decoded depend on the features of Bevy, such as `wav` and `vorbis`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the source cannot be decoded, as [`Decodable::decoder`](bevy::audio::Decodable::decoder) does.
+    #[cfg(feature = "bevy_audio")]
+    pub fn analyze_audio_source(
+        &self,
+        source: &bevy::audio::AudioSource,
+    ) -> LipSyncTimeline {
+        use bevy::audio::{Decodable, Source};
+        let decoder = source.decoder();
+        let channels = decoder.channels();
+        let sample_rate = decoder.sample_rate();
+        let samples = decoder.collect::<Vec<i16>>();
+        self.analyze_i16(&samples, channels, sample_rate)
+    }
+
+    fn analyze_mono(
+        &self,
+        samples: &[f32],
+        sample_rate: u32,
+    ) -> LipSyncTimeline {
+        if sample_rate == 0 || self.frame_rate <= 0.0 {
+            return LipSyncTimeline::default();
+        }
+        let sample_rate = sample_rate as f32;
+        let window_len = ((self.window * sample_rate) as usize).max(2);
+        let frame_count = (samples.len() as f32 / sample_rate * self.frame_rate).ceil() as usize;
+
+        let mut previous = VisemeWeights::default();
+        let frames = (0..frame_count)
+            .map(|frame| {
+                let center = (frame as f32 / self.frame_rate * sample_rate) as usize;
+                let start = center.saturating_sub(window_len / 2);
+                let end = (start + window_len).min(samples.len());
+                let weights = self.analyze_window(&samples[start..end], sample_rate);
+                previous = weights.lerp(&previous, self.smoothing.clamp(0.0, 1.0));
+                previous
+            })
+            .collect();
+        LipSyncTimeline {
+            frame_rate: self.frame_rate,
+            frames,
+        }
+    }
+
+    fn analyze_window(
+        &self,
+        window: &[f32],
+        sample_rate: f32,
+    ) -> VisemeWeights {
+        if window.len() < 2 {
+            return VisemeWeights::default();
+        }
+        let rms = (window.iter().map(|s| s * s).sum::<f32>() / window.len() as f32).sqrt();
+        let db = 20.0 * rms.max(f32::EPSILON).log10();
+        let range = (self.max_volume_db - self.min_volume_db).max(f32::EPSILON);
+        let volume = ((db - self.min_volume_db) / range).clamp(0.0, 1.0);
+        if volume <= 0.0 {
+            return VisemeWeights::default();
+        }
+
+        let spectrum = spectrum(window, sample_rate);
+        let (f1, f2) = formants(&spectrum);
+        let scores = VOWEL_FORMANTS.map(|(vowel_f1, vowel_f2)| {
+            let d1 = (f1 - vowel_f1) / 200.0;
+            let d2 = (f2 - vowel_f2) / 500.0;
+            (-(d1 * d1 + d2 * d2) * self.sharpness).exp()
+        });
+        let total = scores.iter().sum::<f32>();
+        if total <= f32::EPSILON {
+            return VisemeWeights::default();
+        }
+        let [aa, ih, ou, ee, oh] = scores.map(|score| score / total * volume);
+        VisemeWeights { aa, ih, ou, ee, oh }
+    }
+}
+
+/// Returns the power of the frequencies from [`MIN_FREQUENCY`] to [`MAX_FREQUENCY`] using the Goertzel algorithm.
+fn spectrum(
+    window: &[f32],
+    sample_rate: f32,
+) -> Vec<(f32, f32)> {
+    let len = window.len();
+    // Pre-emphasis flattens the spectral tilt of voice, and the Hann window reduces leakage.
+    let samples = window
+        .iter()
+        .enumerate()
+        .map(|(i, sample)| {
+            let previous = if i == 0 { 0.0 } else { window[i - 1] };
+            let hann = 0.5 - 0.5 * (TAU * i as f32 / (len - 1) as f32).cos();
+            (sample - 0.97 * previous) * hann
+        })
+        .collect::<Vec<_>>();
+    let max_frequency = MAX_FREQUENCY.min(sample_rate / 2.0);
+    let mut frequency = MIN_FREQUENCY;
+    let mut powers = Vec::new();
+    while frequency <= max_frequency {
+        let coefficient = 2.0 * (TAU * frequency / sample_rate).cos();
+        let (mut s1, mut s2) = (0.0_f32, 0.0_f32);
+        for sample in &samples {
+            let s = sample + coefficient * s1 - s2;
+            s2 = s1;
+            s1 = s;
+        }
+        powers.push((frequency, s1 * s1 + s2 * s2 - coefficient * s1 * s2));
+        frequency += FREQUENCY_STEP;
+    }
+    powers
+}
+
+/// Returns the first and the second formants from the peaks of the spectrum.
+fn formants(spectrum: &[(f32, f32)]) -> (f32, f32) {
+    let smoothed = spectrum
+        .iter()
+        .enumerate()
+        .map(|(i, (frequency, _))| {
+            let neighbors = &spectrum[i.saturating_sub(1)..(i + 2).min(spectrum.len())];
+            let power = neighbors.iter().map(|(_, p)| p).sum::<f32>() / neighbors.len() as f32;
+            (*frequency, power)
+        })
+        .collect::<Vec<_>>();
+    let peak = |min: f32, max: f32| {
+        smoothed
+            .iter()
+            .filter(|(frequency, _)| (min..=max).contains(frequency))
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(frequency, _)| *frequency)
+    };
+    let f1 = peak(200.0, 1000.0).unwrap_or(500.0);
+    let f2 = peak((f1 + 300.0).max(700.0), 3000.0).unwrap_or(1500.0);
+    (f1, f2)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::lip_sync::audio::LipSyncAnalyzer;
+    use std::f32::consts::TAU;
+
+    const SAMPLE_RATE: u32 = 16000;
+
+    fn vowel(
+        f1: f32,
+        f2: f32,
+    ) -> Vec<f32> {
+        (0..SAMPLE_RATE / 2)
+            .map(|i| {
+                let t = i as f32 / SAMPLE_RATE as f32;
+                0.3 * (TAU * f1 * t).sin() + 0.2 * (TAU * f2 * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detect_vowels() {
+        let analyzer = LipSyncAnalyzer::default();
+
+        let timeline = analyzer.analyze(&vowel(800.0, 1200.0), 1, SAMPLE_RATE);
+        let frame = timeline.frames[timeline.frames.len() / 2];
+        assert!(frame.ih < frame.aa && frame.ou < frame.aa && frame.ee < frame.aa);
+
+        let timeline = analyzer.analyze(&vowel(300.0, 2300.0), 1, SAMPLE_RATE);
+        let frame = timeline.frames[timeline.frames.len() / 2];
+        assert!(frame.aa < frame.ih && frame.ou < frame.ih && frame.oh < frame.ih);
+    }
+
+    #[test]
+    fn silence() {
+        let timeline = LipSyncAnalyzer::default().analyze(&[0.0; 8000], 1, SAMPLE_RATE);
+        assert_eq!(timeline.frames.len(), 30);
+        assert!(timeline.frames.iter().all(|frame| frame.aa == 0.0));
+    }
+}
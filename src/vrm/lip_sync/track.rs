@@ -0,0 +1,350 @@
+//! This module defines [`LipSyncTrack`], which is created from timed phonemes such as the output of TTS.
+
+use crate::error::VrmError;
+use crate::vrm::lip_sync::VisemeWeights;
+use bevy::asset::io::Reader;
+use bevy::asset::{AssetLoader, LoadContext};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// A phoneme with its time range in seconds.
+#[derive(Serialize, Deserialize, Reflect, Debug, Clone, PartialEq)]
+pub struct TimedPhoneme {
+    /// `ARPAbet` (`AA1`, `M`), Japanese kana (`か`, `ン`) or romaji (`ka`, `n`).
+    ///
+    /// Uppercase labels are read as `ARPAbet`, so write the romaji in lowercase.
+    /// `sil`, `sp`, `pau` and `_` are silences, and `ー` extends the previous vowel.
+    pub phoneme: String,
+    pub start: f32,
+    pub end: f32,
+}
+
+/// The viseme track converted from timed phonemes.
+///
+/// This can be loaded from a JSON file with the `lipsync.json` extension:
+///
+/// ```json
+/// {
+///   "coarticulation": 0.06,
+///   "phonemes": [
+///     { "phoneme": "k", "start": 0.0, "end": 0.05 },
+///     { "phoneme": "o", "start": 0.05, "end": 0.2 },
+///     { "phoneme": "n", "start": 0.2, "end": 0.3 }
+///   ]
+/// }
+/// ```
+///
+/// Please refer to [`LipSync`](crate::prelude::LipSync) for playing it.
+#[derive(Asset, Reflect, Debug, Clone, PartialEq)]
+pub struct LipSyncTrack {
+    phonemes: Vec<TimedPhoneme>,
+    /// The seconds that adjacent phonemes blend into each other at their boundary.
+    pub coarticulation: f32,
+    /// The viseme of each phoneme, resolved in [`LipSyncTrack::new`].
+    visemes: Vec<VisemeWeights>,
+}
+
+impl LipSyncTrack {
+    /// The default value of [`LipSyncTrack::coarticulation`].
+    pub const DEFAULT_COARTICULATION: f32 = 0.06;
+
+    /// Returns [`VrmError::InvalidLipSyncTime`] if a time or `coarticulation` is not finite or negative.
+    pub fn new(
+        phonemes: Vec<TimedPhoneme>,
+        coarticulation: f32,
+    ) -> Result<Self, VrmError> {
+        let is_valid = |seconds: f32| seconds.is_finite() && 0.0 <= seconds;
+        if !is_valid(coarticulation) {
+            return Err(VrmError::InvalidLipSyncTime(format!(
+                "coarticulation {coarticulation}"
+            )));
+        }
+        if let Some(timed) = phonemes
+            .iter()
+            .find(|timed| !is_valid(timed.start) || !is_valid(timed.end))
+        {
+            return Err(VrmError::InvalidLipSyncTime(format!(
+                "{}..{} of the phoneme {}",
+                timed.start, timed.end, timed.phoneme
+            )));
+        }
+        let mut previous = VisemeWeights::default();
+        let visemes = phonemes
+            .iter()
+            .map(|timed| {
+                if timed.phoneme != "ー" {
+                    previous = phoneme_viseme(&timed.phoneme).unwrap_or_default();
+                }
+                previous
+            })
+            .collect();
+        Ok(Self {
+            phonemes,
+            coarticulation,
+            visemes,
+        })
+    }
+
+    /// Returns the phonemes in the order of time.
+    #[inline]
+    pub fn phonemes(&self) -> &[TimedPhoneme] {
+        &self.phonemes
+    }
+
+    /// Returns the time when the mouth finishes closing after the last phoneme.
+    ///
+    /// Times that [`Duration`] cannot represent are clamped.
+    pub fn duration(&self) -> Duration {
+        let end = self
+            .phonemes
+            .iter()
+            .map(|timed| timed.end)
+            .fold(0.0, f32::max);
+        Duration::try_from_secs_f32((end + self.coarticulation.max(0.0)).max(0.0))
+            .unwrap_or(Duration::MAX)
+    }
+
+    /// Returns the viseme weights at `time`.
+    ///
+    /// Each phoneme fades in and out over `coarticulation` seconds centered on its boundaries,
+    /// so the visemes of adjacent phonemes crossfade.
+    pub fn sample(
+        &self,
+        time: Duration,
+    ) -> VisemeWeights {
+        let t = time.as_secs_f32();
+        let c = self.coarticulation.max(0.0);
+        let rise = |edge: f32| {
+            if c <= 0.0 {
+                return if edge <= t { 1.0 } else { 0.0 };
+            }
+            let x = ((t - (edge - c)) / (2.0 * c)).clamp(0.0, 1.0);
+            x * x * (3.0 - 2.0 * x)
+        };
+        let mut weights = VisemeWeights::default();
+        for (timed, viseme) in self.phonemes.iter().zip(self.visemes.iter()) {
+            if t < timed.start - c || timed.end + c < t {
+                continue;
+            }
+            let influence = rise(timed.start) * (1.0 - rise(timed.end));
+            let scaled = viseme.scale(influence);
+            weights.aa += scaled.aa;
+            weights.ih += scaled.ih;
+            weights.ou += scaled.ou;
+            weights.ee += scaled.ee;
+            weights.oh += scaled.oh;
+        }
+        VisemeWeights {
+            aa: weights.aa.min(1.0),
+            ih: weights.ih.min(1.0),
+            ou: weights.ou.min(1.0),
+            ee: weights.ee.min(1.0),
+            oh: weights.oh.min(1.0),
+        }
+    }
+}
+
+/// Returns the viseme of the phoneme, or `None` if it is unknown.
+///
+/// Silences and closed-mouth consonants such as `M` and `ん` return all zeros.
+pub fn phoneme_viseme(phoneme: &str) -> Option<VisemeWeights> {
+    let phoneme = phoneme.trim();
+    arpabet_viseme(phoneme)
+        .or_else(|| kana_viseme(phoneme))
+        .or_else(|| romaji_viseme(phoneme))
+}
+
+const fn viseme(
+    aa: f32,
+    ih: f32,
+    ou: f32,
+    ee: f32,
+    oh: f32,
+) -> VisemeWeights {
+    VisemeWeights { aa, ih, ou, ee, oh }
+}
+
+const CLOSED: VisemeWeights = viseme(0.0, 0.0, 0.0, 0.0, 0.0);
+const AA: VisemeWeights = viseme(1.0, 0.0, 0.0, 0.0, 0.0);
+const IH: VisemeWeights = viseme(0.0, 1.0, 0.0, 0.0, 0.0);
+const OU: VisemeWeights = viseme(0.0, 0.0, 1.0, 0.0, 0.0);
+const EE: VisemeWeights = viseme(0.0, 0.0, 0.0, 1.0, 0.0);
+const OH: VisemeWeights = viseme(0.0, 0.0, 0.0, 0.0, 1.0);
+
+fn arpabet_viseme(phoneme: &str) -> Option<VisemeWeights> {
+    // Vowels carry the stress as a trailing digit, such as `AA1`.
+    let phoneme = phoneme.trim_end_matches(|c: char| c.is_ascii_digit());
+    if !phoneme.chars().all(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+    Some(match phoneme {
+        "AA" | "AE" | "AY" | "AW" => AA,
+        "AH" => viseme(0.7, 0.0, 0.0, 0.0, 0.0),
+        "AO" | "OW" | "OY" => OH,
+        "EH" | "EY" => EE,
+        "ER" => viseme(0.0, 0.0, 0.5, 0.0, 0.3),
+        "IH" | "IY" => IH,
+        "UH" | "UW" => OU,
+        "B" | "P" | "M" => CLOSED,
+        "W" => viseme(0.0, 0.0, 0.7, 0.0, 0.0),
+        "R" | "CH" | "JH" | "SH" | "ZH" => viseme(0.0, 0.0, 0.4, 0.0, 0.0),
+        "Y" => viseme(0.0, 0.4, 0.0, 0.0, 0.0),
+        "F" | "V" | "S" | "Z" | "T" | "D" | "N" | "TH" | "DH" => viseme(0.0, 0.3, 0.0, 0.0, 0.0),
+        "L" | "K" | "G" | "NG" | "HH" => viseme(0.3, 0.0, 0.0, 0.0, 0.0),
+        _ => return None,
+    })
+}
+
+fn kana_viseme(phoneme: &str) -> Option<VisemeWeights> {
+    const A: &str = "あかさたなはまやらわがざだばぱぁゃゎアカサタナハマヤラワガザダバパァャヮ";
+    const I: &str = "いきしちにひみりぎじぢびぴぃイキシチニヒミリギジヂビピィ";
+    const U: &str = "うくすつぬふむゆるぐずづぶぷぅゅゔウクスツヌフムユルグズヅブプゥュヴ";
+    const E: &str = "えけせてねへめれげぜでべぺぇエケセテネヘメレゲゼデベペェ";
+    const O: &str = "おこそとのほもよろをごぞどぼぽぉょオコソトノホモヨロヲゴゾドボポォョ";
+    const CLOSED_KANA: &str = "んっンッ";
+    // The vowel of a contracted sound such as `きゃ` is the one of the last kana.
+    let last = phoneme.chars().last()?;
+    [
+        (A, AA),
+        (I, IH),
+        (U, OU),
+        (E, EE),
+        (O, OH),
+        (CLOSED_KANA, CLOSED),
+    ]
+    .into_iter()
+    .find(|(kana, _)| kana.contains(last))
+    .map(|(_, viseme)| viseme)
+}
+
+fn romaji_viseme(phoneme: &str) -> Option<VisemeWeights> {
+    let phoneme = phoneme.to_ascii_lowercase();
+    match phoneme.as_str() {
+        "sil" | "sp" | "pau" | "_" | "" | "n" | "nn" | "q" | "cl" | "m" => return Some(CLOSED),
+        _ => {}
+    }
+    if !phoneme.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    match phoneme.chars().last()? {
+        'a' => Some(AA),
+        'i' => Some(IH),
+        'u' => Some(OU),
+        'e' => Some(EE),
+        'o' => Some(OH),
+        // Consonants without a vowel, such as `k` of `k`, `o`.
+        _ => Some(viseme(0.0, 0.2, 0.0, 0.0, 0.0)),
+    }
+}
+
+#[derive(Deserialize)]
+struct LipSyncTrackFile {
+    #[serde(default = "LipSyncTrackFile::default_coarticulation")]
+    coarticulation: f32,
+    phonemes: Vec<TimedPhoneme>,
+}
+
+impl LipSyncTrackFile {
+    const fn default_coarticulation() -> f32 {
+        LipSyncTrack::DEFAULT_COARTICULATION
+    }
+}
+
+#[derive(Default)]
+pub(super) struct LipSyncTrackLoader;
+
+impl AssetLoader for LipSyncTrackLoader {
+    type Asset = LipSyncTrack;
+    type Settings = ();
+    type Error = VrmError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _: &Self::Settings,
+        _: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let file: LipSyncTrackFile =
+            serde_json::from_slice(&bytes).map_err(VrmError::InvalidLipSyncTrack)?;
+        LipSyncTrack::new(file.phonemes, file.coarticulation)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["lipsync.json"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::lip_sync::track::{
+        phoneme_viseme, LipSyncTrack, LipSyncTrackFile, TimedPhoneme,
+    };
+    use std::time::Duration;
+
+    #[test]
+    fn map_phonemes() {
+        assert_eq!(phoneme_viseme("AA1").unwrap().aa, 1.0);
+        assert_eq!(phoneme_viseme("IY0").unwrap().ih, 1.0);
+        assert_eq!(phoneme_viseme("M").unwrap().aa, 0.0);
+        assert_eq!(phoneme_viseme("か").unwrap().aa, 1.0);
+        assert_eq!(phoneme_viseme("キョ").unwrap().oh, 1.0);
+        assert_eq!(phoneme_viseme("shi").unwrap().ih, 1.0);
+        assert_eq!(phoneme_viseme("e").unwrap().ee, 1.0);
+        assert!(phoneme_viseme("?").is_none());
+    }
+
+    #[test]
+    fn crossfade_phonemes() -> TestResult {
+        let file: LipSyncTrackFile = serde_json::from_str(
+            r#"{
+                "phonemes": [
+                    { "phoneme": "a", "start": 0.0, "end": 0.2 },
+                    { "phoneme": "ー", "start": 0.2, "end": 0.3 },
+                    { "phoneme": "i", "start": 0.3, "end": 0.5 }
+                ]
+            }"#,
+        )?;
+        let track = LipSyncTrack::new(file.phonemes, file.coarticulation)?;
+
+        let weights = track.sample(Duration::from_secs_f32(0.1));
+        assert_eq!((weights.aa, weights.ih), (1.0, 0.0));
+        let weights = track.sample(Duration::from_secs_f32(0.22));
+        assert!((weights.aa - 1.0).abs() < 1e-4 && weights.ih == 0.0);
+        let weights = track.sample(Duration::from_secs_f32(0.3));
+        assert!((weights.aa - 0.5).abs() < 1e-4 && (weights.ih - 0.5).abs() < 1e-4);
+        let weights = track.sample(track.duration());
+        assert_eq!(weights.ih, 0.0);
+        success!()
+    }
+
+    #[test]
+    fn reject_invalid_times() -> TestResult {
+        for json in [
+            r#"{ "phonemes": [{ "phoneme": "a", "start": -0.1, "end": 0.2 }] }"#,
+            r#"{ "phonemes": [{ "phoneme": "a", "start": 0.0, "end": 1e39 }] }"#,
+            r#"{ "coarticulation": -1, "phonemes": [] }"#,
+        ] {
+            let file: LipSyncTrackFile = serde_json::from_str(json)?;
+            assert!(
+                LipSyncTrack::new(file.phonemes, file.coarticulation).is_err(),
+                "{json}"
+            );
+        }
+        let nan = TimedPhoneme {
+            phoneme: "a".to_string(),
+            start: f32::NAN,
+            end: 0.2,
+        };
+        assert!(LipSyncTrack::new(vec![nan], LipSyncTrack::DEFAULT_COARTICULATION).is_err());
+        let file: LipSyncTrackFile = serde_json::from_str(
+            r#"{ "phonemes": [{ "phoneme": "a", "start": 0.0, "end": 0.2 }] }"#,
+        )?;
+        assert!(LipSyncTrack::new(file.phonemes, file.coarticulation).is_ok());
+        success!()
+    }
+}
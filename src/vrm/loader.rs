@@ -1,11 +1,26 @@
+use crate::error::VrmError;
+use crate::vrm::expressions::VrmExpressionRegistry;
+use crate::vrm::gltf::extensions::vrm0::scene::rotate_vrm0_scene;
+use crate::vrm::gltf::extensions::vrm0::GltfDocument;
+use crate::vrm::gltf::extensions::{obtain_extensions, parse_vrm0, VrmExtensions, VRMC_VRM};
+use crate::vrm::gltf::node_names;
+use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
+use crate::vrm::meta::vrm_meta::VrmMeta;
+use crate::vrm::mtoon::VrmcMaterialRegistry;
+use crate::vrm::spring_bone::registry::{
+    SpringColliderRegistry, SpringJointPropsRegistry, SpringNodeRegistry,
+};
+use crate::vrm::validator::VrmValidationReport;
 use bevy::app::{App, Plugin};
-use bevy::asset::io::Reader;
+use bevy::asset::io::{Reader, SliceReader};
 use bevy::asset::{Asset, AssetLoader, Handle, LoadContext};
-use bevy::gltf::{Gltf, GltfAssetLabel, GltfError, GltfLoader, GltfLoaderSettings};
-use bevy::image::{CompressedImageFormats, Image};
-use bevy::prelude::{AssetApp, Component, TypePath};
+use bevy::gltf::{Gltf, GltfAssetLabel, GltfLoader, GltfLoaderSettings};
+use bevy::image::CompressedImageFormats;
+use bevy::prelude::{AssetApp, Component, Event, TypePath};
+use bevy::render::render_asset::RenderAssetUsages;
 use bevy::render::renderer::RenderDevice;
 use bevy::utils::default;
+use serde::{Deserialize, Serialize};
 
 pub struct VrmLoaderPlugin;
 
@@ -44,6 +59,10 @@ impl Plugin for VrmLoaderPlugin {
 /// - [`SceneRoot`](bevy::scene::SceneRoot)
 /// - Components hold the entity of each bone, refer to [here](crate::vrm::humanoid_bone) for more details.
 ///
+/// When the asset is modified while the app is running, e.g. with the `file_watcher` feature of Bevy,
+/// the VRM is rebuilt on the same entity, keeping [`LookAt`](crate::prelude::LookAt),
+/// the playing VRMA and the expression weights.
+///
 /// ```no_run
 /// use bevy::prelude::*;
 /// use bevy_vrm1::prelude::*;
@@ -58,39 +77,186 @@ impl Plugin for VrmLoaderPlugin {
 #[derive(Debug, Component)]
 pub struct VrmHandle(pub Handle<VrmAsset>);
 
+/// An event that is emitted when VRM could not be spawned from [`VrmHandle`].
+///
+/// This event is emitted as a trigger.
+/// The target of the trigger is the entity that had [`VrmHandle`].
+#[derive(Debug, Event)]
+pub struct VrmLoadFailed {
+    pub error: VrmError,
+}
+
+/// Represents a VRM asset.
+///
+/// The extensions are parsed and validated when the asset is loaded,
+/// and the registries built from them are cloned into each spawned VRM.
 #[derive(Debug, Asset, TypePath)]
 pub struct VrmAsset {
     pub(crate) gltf: Gltf,
-    pub(crate) images: Vec<Handle<Image>>,
+    pub(crate) settings: VrmLoaderSettings,
+    /// The VRM extensions of the glTF.
+    ///
+    /// VRM 0.x is converted into the VRM 1.0 structures.
+    pub extensions: VrmExtensions,
+    /// The meta information converted from [`VrmExtensions`].
+    pub meta: VrmMeta,
+    /// The result of [`VrmValidator`](crate::prelude::VrmValidator).
+    ///
+    /// Models with [fatal](crate::prelude::VrmValidationIssue::is_fatal) errors are not loaded,
+    /// so this contains the other errors and the warnings, e.g. to show them to the uploader.
+    pub validation: VrmValidationReport,
+    /// The node names of the humanoid bones.
+    pub humanoid_bones: HumanoidBoneRegistry,
+    /// The morph targets bound to the expressions.
+    pub expressions: VrmExpressionRegistry,
+    /// The `MToon` properties of the materials.
+    ///
+    /// This is `None` if [`VrmLoaderSettings::mtoon`] is `false`.
+    pub materials: Option<VrmcMaterialRegistry>,
+    pub(crate) spring_bone: Option<(
+        SpringJointPropsRegistry,
+        SpringColliderRegistry,
+        SpringNodeRegistry,
+    )>,
+}
+
+/// The settings to load a VRM.
+///
+/// The same file can be loaded with different settings through [`AssetServer::load_with_settings`](bevy::prelude::AssetServer::load_with_settings).
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn spawn_preview(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+/// ){
+///     commands.spawn(VrmHandle(asset_server.load_with_settings(
+///         "<vrm>.vrm",
+///         |settings: &mut VrmLoaderSettings| {
+///             settings.mtoon = false;
+///             settings.spring_bone = false;
+///         },
+///     )));
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VrmLoaderSettings {
+    /// If `false`, the materials are not converted into [`MToonMaterial`](crate::prelude::MToonMaterial)
+    /// and remain [`StandardMaterial`](bevy::prelude::StandardMaterial).
+    pub mtoon: bool,
+    /// If `false`, the spring bones are not simulated.
+    pub spring_bone: bool,
+    /// If `false`, [`LookAtProperties`](crate::prelude::LookAtProperties) is not inserted,
+    /// so the eyes do not follow [`LookAt`](crate::prelude::LookAt).
+    pub look_at: bool,
+    /// The index of the glTF scene to spawn.
+    pub scene: usize,
+    /// Passed to [`GltfLoaderSettings::load_meshes`].
+    pub load_meshes: RenderAssetUsages,
+    /// Passed to [`GltfLoaderSettings::load_materials`].
+    pub load_materials: RenderAssetUsages,
+    /// If `false`, the animation clips contained in the glTF are not kept in [`VrmAsset`].
+    pub load_animations: bool,
+}
+
+impl Default for VrmLoaderSettings {
+    fn default() -> Self {
+        Self {
+            mtoon: true,
+            spring_bone: true,
+            look_at: true,
+            scene: 0,
+            load_meshes: RenderAssetUsages::default(),
+            load_materials: RenderAssetUsages::default(),
+            load_animations: true,
+        }
+    }
 }
 
 struct VrmLoader(GltfLoader);
 
 impl AssetLoader for VrmLoader {
     type Asset = VrmAsset;
-    type Settings = ();
-    type Error = GltfError;
+    type Settings = VrmLoaderSettings;
+    type Error = VrmError;
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
-        let settings = GltfLoaderSettings {
+        let gltf_settings = GltfLoaderSettings {
+            load_meshes: settings.load_meshes,
+            load_materials: settings.load_materials,
             include_source: true,
             ..default()
         };
-        let gltf = self.0.load(reader, &settings, load_context).await?;
-        Ok(VrmAsset {
-            images: gltf
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let bytes = rotate_vrm0_scene(bytes)?;
+        let mut gltf = self
+            .0
+            .load(&mut SliceReader::new(&bytes), &gltf_settings, load_context)
+            .await?;
+        if !settings.load_animations {
+            gltf.animations.clear();
+            gltf.named_animations.clear();
+        }
+        if gltf.scenes.get(settings.scene).is_none() {
+            return Err(VrmError::MissingScene(settings.scene));
+        }
+        let json = obtain_extensions(&gltf).ok_or(VrmError::MissingExtension(VRMC_VRM))?;
+        let vrm0 = parse_vrm0(json)?;
+        let (extensions, validation) =
+            VrmExtensions::from_document(json, vrm0.as_ref(), &GltfDocument::from(&gltf))?;
+        let node_names = node_names(&gltf);
+        let materials = if settings.mtoon {
+            let images = gltf
                 .source
-                .as_ref()
-                .unwrap()
-                .textures()
+                .iter()
+                .flat_map(|source| source.textures())
                 .map(|tex| {
                     load_context.get_label_handle(GltfAssetLabel::Texture(tex.index()).to_string())
                 })
-                .collect(),
+                .collect();
+            Some(VrmcMaterialRegistry::from_gltf(
+                &gltf,
+                vrm0.as_ref(),
+                images,
+            )?)
+        } else {
+            None
+        };
+        let spring_bone = extensions
+            .vrmc_spring_bone
+            .as_ref()
+            .filter(|_| settings.spring_bone)
+            .map(|spring_bone| {
+                (
+                    SpringJointPropsRegistry::new(&spring_bone.all_joints(), &node_names),
+                    SpringColliderRegistry::new(&spring_bone.colliders, &node_names),
+                    SpringNodeRegistry::new(spring_bone, &node_names),
+                )
+            });
+        Ok(VrmAsset {
+            settings: settings.clone(),
+            validation,
+            meta: extensions
+                .vrmc_vrm
+                .meta
+                .as_ref()
+                .map(VrmMeta::from)
+                .unwrap_or_default(),
+            humanoid_bones: HumanoidBoneRegistry::new(
+                &extensions.vrmc_vrm.humanoid.human_bones,
+                &node_names,
+            ),
+            expressions: VrmExpressionRegistry::new(&extensions, &node_names, &gltf.materials),
+            materials,
+            spring_bone,
+            extensions,
             gltf,
         })
     }
@@ -99,3 +265,209 @@ impl AssetLoader for VrmLoader {
         &["vrm"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::{LookAtProperties, VrmExpression};
+    use crate::success;
+    use crate::tests::{test_asset_app, wait_for_load, TestResult};
+    use crate::vrm::gltf::node_names;
+    use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
+    use crate::vrm::initialize::spawn_vrm;
+    use crate::vrm::loader::{VrmAsset, VrmHandle, VrmLoaderPlugin, VrmLoaderSettings};
+    use crate::vrm::meta::usage_policy::VrmUsagePolicy;
+    use crate::vrm::spring_bone::registry::SpringNodeRegistry;
+    use bevy::app::App;
+    use bevy::asset::{AssetApp, AssetServer, Assets, Handle, RenderAssetUsages};
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::gltf::GltfNode;
+    use bevy::prelude::{Mesh3d, Name};
+    use bevy::scene::{Scene, SceneRoot};
+
+    fn fixture() -> serde_json::Value {
+        serde_json::from_str(include_str!("loader.json")).unwrap()
+    }
+
+    fn load_vrm(
+        json: &serde_json::Value,
+        settings: impl Fn(&mut VrmLoaderSettings) + Send + Sync + 'static,
+    ) -> Result<(App, Handle<VrmAsset>), String> {
+        let bytes = serde_json::to_vec(json).map_err(|e| e.to_string())?;
+        let mut app = test_asset_app(&[("model.vrm", &bytes)]);
+        app.init_asset::<VrmAsset>().add_plugins(VrmLoaderPlugin);
+        app.finish();
+        let handle = app
+            .world()
+            .resource::<AssetServer>()
+            .load_with_settings("memory://model.vrm", settings);
+        wait_for_load(&mut app, &handle)?;
+        Ok((app, handle))
+    }
+
+    #[test]
+    fn refuse_missing_hips() -> TestResult {
+        let mut json = fixture();
+        load_vrm(&json, |_| {})?;
+
+        json["extensions"]["VRMC_vrm"]["humanoid"]["humanBones"]
+            .as_object_mut()
+            .ok_or("no humanBones")?
+            .remove("hips");
+        let error = load_vrm(&json, |_| {}).err().ok_or("loaded without hips")?;
+        assert!(
+            error.contains("Not found the required humanoid bone: hips"),
+            "{error}"
+        );
+        success!()
+    }
+
+    #[test]
+    fn refuse_unsupported_spec_version() -> TestResult {
+        let mut json = fixture();
+        json["extensions"]["VRMC_vrm"]["specVersion"] = "2.0".into();
+        let error = load_vrm(&json, |_| {}).err().ok_or("loaded VRMC_vrm 2.0")?;
+        assert!(
+            error.contains("Unsupported spec version of VRMC_vrm: 2.0"),
+            "{error}"
+        );
+        success!()
+    }
+
+    fn asset<'a>(
+        app: &'a App,
+        handle: &Handle<VrmAsset>,
+    ) -> Result<&'a VrmAsset, String> {
+        app.world()
+            .resource::<Assets<VrmAsset>>()
+            .get(handle)
+            .ok_or_else(|| "asset is not found".to_string())
+    }
+
+    /// Returns the number of the mesh entities in the scene to spawn.
+    fn scene_meshes(
+        app: &App,
+        vrm: &VrmAsset,
+    ) -> Result<usize, String> {
+        let scene = app
+            .world()
+            .resource::<Assets<Scene>>()
+            .get(&vrm.gltf.scenes[vrm.settings.scene])
+            .ok_or_else(|| "scene is not found".to_string())?;
+        Ok(scene
+            .world
+            .iter_entities()
+            .filter(|entity| entity.contains::<Mesh3d>())
+            .count())
+    }
+
+    #[test]
+    fn skip_gltf_assets_by_settings() -> TestResult {
+        let json = fixture();
+        let (app, handle) = load_vrm(&json, |_| {})?;
+        let vrm = asset(&app, &handle)?;
+        assert_eq!(scene_meshes(&app, vrm)?, 1);
+        assert_eq!(vrm.gltf.materials.len(), 1);
+        assert_eq!(vrm.gltf.animations.len(), 1);
+
+        let (app, handle) = load_vrm(&json, |settings| {
+            settings.load_meshes = RenderAssetUsages::empty();
+            settings.load_materials = RenderAssetUsages::empty();
+            settings.load_animations = false;
+        })?;
+        let vrm = asset(&app, &handle)?;
+        assert_eq!(scene_meshes(&app, vrm)?, 0);
+        assert!(vrm.gltf.materials.is_empty());
+        assert!(vrm.gltf.animations.is_empty());
+        assert!(vrm.gltf.named_animations.is_empty());
+        success!()
+    }
+
+    #[test]
+    fn skip_vrm_features_by_settings() -> TestResult {
+        let json = fixture();
+        let (app, handle) = load_vrm(&json, |_| {})?;
+        let vrm = asset(&app, &handle)?;
+        assert!(vrm.materials.is_some());
+        assert!(vrm.spring_bone.is_some());
+
+        let (app, handle) = load_vrm(&json, |settings| {
+            settings.mtoon = false;
+            settings.spring_bone = false;
+        })?;
+        let vrm = asset(&app, &handle)?;
+        assert!(vrm.materials.is_none());
+        assert!(vrm.spring_bone.is_none());
+        success!()
+    }
+
+    #[test]
+    fn spawn_by_look_at_and_scene_settings() -> TestResult {
+        let mut json = fixture();
+        json["scenes"]
+            .as_array_mut()
+            .ok_or("no scenes")?
+            .push(serde_json::json!({ "nodes": [0] }));
+        let spawn = |settings: fn(&mut VrmLoaderSettings)| -> TestResult<(bool, bool)> {
+            let (mut app, handle) = load_vrm(&json, settings)?;
+            app.init_resource::<VrmUsagePolicy>();
+            let vrm = app.world_mut().spawn(VrmHandle(handle.clone())).id();
+            app.world_mut().run_system_once(spawn_vrm)?;
+            let scenes = &asset(&app, &handle)?.gltf.scenes;
+            let vrm = app.world().entity(vrm);
+            let scene = vrm.get::<SceneRoot>().ok_or("scene is not spawned")?;
+            Ok((vrm.contains::<LookAtProperties>(), scene.0 == scenes[1]))
+        };
+
+        assert_eq!(spawn(|_| {})?, (true, false));
+        assert_eq!(
+            spawn(|settings| {
+                settings.look_at = false;
+                settings.scene = 1;
+            })?,
+            (false, true)
+        );
+
+        let error = load_vrm(&json, |settings| settings.scene = 2)
+            .err()
+            .ok_or("loaded a missing scene")?;
+        assert!(error.contains("Not found the scene at index 2"), "{error}");
+        success!()
+    }
+
+    #[test]
+    fn build_registries_with_gltf_node_names() -> TestResult {
+        let mut json = fixture();
+        // An unnamed node is named after its index.
+        json["nodes"][17]
+            .as_object_mut()
+            .ok_or("no node")?
+            .remove("name");
+        let (app, handle) = load_vrm(&json, |_| {})?;
+        let vrm = asset(&app, &handle)?;
+        let gltf_nodes = app.world().resource::<Assets<GltfNode>>();
+        let names = vrm
+            .gltf
+            .nodes
+            .iter()
+            .map(|node| Some(Name::new(gltf_nodes.get(node)?.name.clone())))
+            .collect::<Option<Vec<_>>>()
+            .ok_or("node is not found")?;
+        assert_eq!(names, node_names(&vrm.gltf));
+
+        let extensions = &vrm.extensions;
+        assert_eq!(
+            *HumanoidBoneRegistry::new(&extensions.vrmc_vrm.humanoid.human_bones, &names),
+            *vrm.humanoid_bones
+        );
+        let aa = &vrm.expressions[&VrmExpression::from("aa")];
+        assert_eq!(aa.nodes[0].name, names[15]);
+        let (_, _, springs) = vrm.spring_bone.as_ref().ok_or("no spring bone")?;
+        let spring_bone = extensions
+            .vrmc_spring_bone
+            .as_ref()
+            .ok_or("no VRMC_springBone")?;
+        assert_eq!(SpringNodeRegistry::new(spring_bone, &names).0, springs.0);
+        assert_eq!(springs.0[0].joints[1], Name::new("GltfNode17"));
+        success!()
+    }
+}
@@ -1,20 +1,39 @@
 //! - [`look at specification(en)`](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_vrm-1.0/lookAt.md)
 //! - [`look at specification(ja)`](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_vrm-1.0/lookAt.ja.md)
 
+mod gaze;
+mod head;
+
 use crate::prelude::*;
 use crate::system_set::VrmSystemSets;
+use crate::vrm::look_at::gaze::{blink_on_gaze_shift, update_attention, GazeState};
+use crate::vrm::look_at::head::{apply_head_look_at, restore_head_look_at, HeadLookAtState};
+use crate::vrm::{BoneRestGlobalTransform, BoneRestTransform};
+use crate::vrma::animation::is_animated_by_vrma;
+use crate::vrma::{VrmAnimationClipHandle, VrmAnimationNodeIndex};
+use bevy::animation::AnimationTarget;
 use bevy::app::{Animation, App, Plugin};
+use bevy::ecs::system::SystemParam;
 use bevy::input::mouse::MouseMotion;
 use bevy::prelude::TransformSystem::TransformPropagate;
 use bevy::prelude::*;
 use bevy::render::camera::RenderTarget;
 use bevy::window::{PrimaryWindow, WindowRef};
 
+pub use gaze::{AttentionTarget, GazeBehavior};
+pub use head::{HeadLookAt, HeadLookAtBone};
+
 /// Holds the entity of looking the target entity.
 /// This component should be inserted into the root entity of the VRM.
 ///
 /// [`LookAt::Cursor`] is used to look at the mouse cursor in the window.
 /// [`LookAt::Target`] is used to look at the specified entity.
+/// [`LookAt::Position`] is used to look at a point in world space.
+///
+/// The gaze is evaluated every frame by default; see [`LookAtUpdateMode`] to evaluate it only when something moves.
+///
+/// Without this component, the eyes follow the `lookAt` of the VRMA played by [`PlayVrma`] if it has one.
+/// This component takes priority over the VRMA while it exists.
 ///
 /// ```no_run
 /// use bevy::prelude::*;
@@ -33,7 +52,7 @@ use bevy::window::{PrimaryWindow, WindowRef};
 ///     ));
 /// }
 /// ```
-#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Reflect)]
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
 #[reflect(Component)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
@@ -44,9 +63,92 @@ pub enum LookAt {
     Cursor { camera: Option<Entity> },
 
     /// Specify the entity of the target.
+    /// The world-space position of its [`GlobalTransform`] is looked at, so the target can have a parent.
     Target(Entity),
+
+    /// Specify the position in world space.
+    Position(Vec3),
 }
 
+/// When [`LookAt`] is evaluated.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// App::new()
+///     .add_plugins((DefaultPlugins, VrmPlugin))
+///     .insert_resource(LookAtUpdateMode::OnChange);
+/// ```
+#[derive(Resource, Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum LookAtUpdateMode {
+    /// Evaluates the gaze every frame.
+    #[default]
+    EveryFrame,
+    /// Evaluates the gaze only when [`LookAt`] changes, the mouse moves,
+    /// or the head, the target or the camera moves.
+    ///
+    /// This suits idle desktop apps that render only on demand.
+    OnChange,
+}
+
+/// The weights of `lookLeft`, `lookRight`, `lookUp` and `lookDown` driven by [`LookAt`]
+/// when the type of [`LookAtProperties`] is [`LookAtType::Expression`].
+#[derive(Component, Reflect, Debug, Default, Copy, Clone, PartialEq)]
+#[reflect(Component)]
+pub(crate) struct LookAtExpressionWeights {
+    look_left: f32,
+    look_right: f32,
+    look_up: f32,
+    look_down: f32,
+}
+
+impl LookAtExpressionWeights {
+    /// Maps yaw and pitch in degrees through the range maps.
+    ///
+    /// Positive yaw looks left and positive pitch looks down;
+    /// both horizontal directions use `rangeMapHorizontalOuter` as the specification defines.
+    fn new(
+        properties: &LookAtProperties,
+        yaw: f32,
+        pitch: f32,
+    ) -> Self {
+        let horizontal = properties
+            .range_map_horizontal_outer
+            .map(yaw)
+            .clamp(0.0, 1.0);
+        let (look_up, look_down) = if 0.0 < pitch {
+            (0.0, properties.range_map_vertical_down.map(pitch))
+        } else {
+            (properties.range_map_vertical_up.map(pitch), 0.0)
+        };
+        Self {
+            look_left: if 0.0 < yaw { horizontal } else { 0.0 },
+            look_right: if yaw < 0.0 { horizontal } else { 0.0 },
+            look_up: look_up.clamp(0.0, 1.0),
+            look_down: look_down.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Returns the weights paired with the expressions.
+    pub(crate) const fn expressions(&self) -> [(VrmExpressionPreset, f32); 4] {
+        [
+            (VrmExpressionPreset::LookLeft, self.look_left),
+            (VrmExpressionPreset::LookRight, self.look_right),
+            (VrmExpressionPreset::LookUp, self.look_up),
+            (VrmExpressionPreset::LookDown, self.look_down),
+        ]
+    }
+}
+
+/// Marks the VRM whose eye bones are rotated by the `lookAt` of the playing VRMA.
+#[derive(Component, Reflect, Debug, Default, Copy, Clone)]
+#[reflect(Component)]
+pub(crate) struct VrmaLookAtEyes;
+
 pub(super) struct LookAtPlugin;
 
 impl Plugin for LookAtPlugin {
@@ -57,55 +159,169 @@ impl Plugin for LookAtPlugin {
         app.register_type::<LookAt>()
             .register_type::<LookAtProperties>()
             .register_type::<LookAtType>()
+            .register_type::<LookAtExpressionWeights>()
+            .register_type::<VrmaLookAtEyes>()
+            .register_type::<LookAtUpdateMode>()
+            .register_type::<HeadLookAt>()
+            .register_type::<HeadLookAtState>()
+            .register_type::<GazeBehavior>()
+            .register_type::<GazeState>()
+            .init_resource::<LookAtUpdateMode>()
             .add_systems(
                 PostUpdate,
-                track_looking_target
-                    .run_if(on_event::<MouseMotion>)
+                (update_attention, track_looking_target, blink_on_gaze_shift)
+                    .chain()
                     .in_set(VrmSystemSets::LookAt)
                     .after(Animation)
                     .after(TransformPropagate),
-            );
+            )
+            .add_systems(
+                PostUpdate,
+                apply_head_look_at
+                    .in_set(VrmSystemSets::LookAt)
+                    .after(Animation)
+                    .before(TransformPropagate),
+            )
+            .add_observer(reset_look_at_expressions)
+            .add_observer(restore_head_look_at);
+    }
+}
+
+/// Obtains the point looked at by the `lookAt` of the playing VRMA.
+#[derive(SystemParam)]
+struct VrmaLookAtSource<'w, 's> {
+    searcher: ChildSearcher<'w, 's>,
+    sources: Query<'w, 's, (&'static GlobalTransform, &'static AnimationTarget)>,
+    players: Query<'w, 's, &'static AnimationPlayer>,
+    vrmas: Query<
+        'w,
+        's,
+        (
+            &'static VrmAnimationNodeIndex,
+            &'static VrmAnimationClipHandle,
+            &'static ChildOf,
+        ),
+    >,
+    clips: Res<'w, Assets<AnimationClip>>,
+}
+
+impl VrmaLookAtSource<'_, '_> {
+    /// Returns the position in world space, or `None` if no playing VRMA animates the gaze.
+    fn target(
+        &self,
+        vrm: Entity,
+    ) -> Option<Vec3> {
+        let root_bone = self.searcher.find_root_bone(vrm)?;
+        let source = self
+            .searcher
+            .find_from_name(root_bone, Vrm::LOOK_AT_SOURCE)?;
+        let (gtf, target) = self.sources.get(source).ok()?;
+        is_animated_by_vrma(vrm, target, &self.players, &self.vrmas, &self.clips)
+            .then(|| gtf.translation())
     }
 }
 
 fn track_looking_target(
     mut commands: Commands,
-    vrms: Query<(
-        &LookAt,
-        &LookAtProperties,
+    mut vrms: Query<(
+        Entity,
+        Option<Ref<LookAt>>,
+        Ref<LookAtProperties>,
         &HeadBoneEntity,
         &LeftEyeBoneEntity,
         &RightEyeBoneEntity,
+        Option<&mut LookAtExpressionWeights>,
+        Option<(&GazeBehavior, &mut GazeState)>,
+        Has<VrmaLookAtEyes>,
     )>,
     cameras: Query<(Entity, &Camera)>,
     transforms: Query<&Transform>,
+    rest_transforms: Query<&BoneRestTransform>,
     global_transforms: Query<&GlobalTransform>,
+    rest_global_transforms: Query<&BoneRestGlobalTransform>,
+    moved: Query<(), Changed<GlobalTransform>>,
     windows: Query<(&Window, Has<PrimaryWindow>)>,
+    mut mouse_motion: EventReader<MouseMotion>,
+    vrma_look_at: VrmaLookAtSource,
+    mode: Res<LookAtUpdateMode>,
+    time: Res<Time>,
 ) {
-    vrms.iter()
-        .for_each(|(look_at, properties, head, left_eye, right_eye)| {
-            let Ok(head_gtf) = global_transforms.get(head.0) else {
-                return;
-            };
-            let Ok(head_tf) = transforms.get(head.0) else {
-                return;
+    let mouse_moved = mouse_motion.read().count() > 0;
+    vrms.iter_mut().for_each(
+        |(
+            vrm,
+            look_at,
+            properties,
+            head,
+            left_eye,
+            right_eye,
+            expression_weights,
+            gaze,
+            vrma_look_at_eyes,
+        )| {
+            let (yaw, pitch) = match look_at {
+                Some(look_at) => {
+                    if *mode == LookAtUpdateMode::OnChange
+                        && gaze.is_none()
+                        && !look_at.is_changed()
+                        && !properties.is_changed()
+                        && !moved.contains(head.0)
+                        && !is_target_moved(&look_at, mouse_moved, &moved, &cameras)
+                    {
+                        return;
+                    }
+                    let Some((yaw, pitch)) = calc_look_at_yaw_pitch(
+                        &look_at,
+                        &properties,
+                        head,
+                        &global_transforms,
+                        &rest_global_transforms,
+                        &cameras,
+                        &windows,
+                    ) else {
+                        return;
+                    };
+                    (yaw, pitch)
+                }
+                None => {
+                    let Some((yaw, pitch)) = vrma_look_at.target(vrm).and_then(|target| {
+                        let look_at_space = calc_look_at_space(
+                            &properties,
+                            head,
+                            &global_transforms,
+                            &rest_global_transforms,
+                        )?;
+                        Some(calc_yaw_pitch(&look_at_space, target))
+                    }) else {
+                        // Release the expressions and the eyes once the VRMA stops animating the gaze.
+                        if let Some(mut current) = expression_weights {
+                            current.set_if_neq(LookAtExpressionWeights::default());
+                        }
+                        if vrma_look_at_eyes {
+                            for eye in [left_eye.0, right_eye.0] {
+                                if let (Ok(tf), Ok(rest)) =
+                                    (transforms.get(eye), rest_transforms.get(eye))
+                                {
+                                    commands.entity(eye).insert(tf.with_rotation(rest.rotation));
+                                }
+                            }
+                            commands.entity(vrm).remove::<VrmaLookAtEyes>();
+                        }
+                        return;
+                    };
+                    if properties.r#type == LookAtType::Bone && !vrma_look_at_eyes {
+                        commands.entity(vrm).insert(VrmaLookAtEyes);
+                    }
+                    (yaw, pitch)
+                }
             };
-            let look_at_space = GlobalTransform::default();
-            let mut look_at_space_tf = look_at_space.reparented_to(head_gtf);
-            look_at_space_tf.translation = Vec3::from(properties.offset_from_head_bone);
-            look_at_space_tf.rotation = head_tf.rotation.inverse();
-            let look_at_space = head_gtf.mul_transform(look_at_space_tf);
-            let Some(target) = calc_target_position(
-                look_at,
-                head.0,
-                &transforms,
-                &global_transforms,
-                &cameras,
-                &windows,
-            ) else {
-                return;
+            let (yaw, pitch) = match gaze {
+                Some((behavior, mut state)) => {
+                    state.seed(vrm);
+                    state.update(behavior, yaw, pitch, time.delta_secs())
+                }
+                None => (yaw, pitch),
             };
-            let (yaw, pitch) = calc_yaw_pitch(&look_at_space, target);
             match properties.r#type {
                 LookAtType::Bone => {
                     apply_bone(
@@ -113,22 +329,93 @@ fn track_looking_target(
                         &transforms,
                         left_eye,
                         right_eye,
-                        properties,
+                        &properties,
                         yaw,
                         pitch,
                     );
                 }
                 LookAtType::Expression => {
-                    todo!("Expression look at is not supported yet");
+                    let weights = LookAtExpressionWeights::new(&properties, yaw, pitch);
+                    match expression_weights {
+                        Some(mut current) => {
+                            current.set_if_neq(weights);
+                        }
+                        None => {
+                            commands.entity(vrm).insert(weights);
+                        }
+                    }
                 }
             }
-        });
+        },
+    );
+}
+
+/// Returns the yaw and the pitch in degrees toward the target of [`LookAt`] seen from the look-at space.
+fn calc_look_at_yaw_pitch(
+    look_at: &LookAt,
+    properties: &LookAtProperties,
+    head: &HeadBoneEntity,
+    global_transforms: &Query<&GlobalTransform>,
+    rest_global_transforms: &Query<&BoneRestGlobalTransform>,
+    cameras: &Query<(Entity, &Camera)>,
+    windows: &Query<(&Window, Has<PrimaryWindow>)>,
+) -> Option<(f32, f32)> {
+    let look_at_space =
+        calc_look_at_space(properties, head, global_transforms, rest_global_transforms)?;
+    let target = calc_target_position(look_at, head.0, global_transforms, cameras, windows)?;
+    Some(calc_yaw_pitch(&look_at_space, target))
+}
+
+/// Returns the look-at space placed at `offsetFromHeadBone` from the head bone.
+///
+/// The space follows the current rotation of the head from its rest pose,
+/// so the eyes only cover the angle that the head does not rotate.
+fn calc_look_at_space(
+    properties: &LookAtProperties,
+    head: &HeadBoneEntity,
+    global_transforms: &Query<&GlobalTransform>,
+    rest_global_transforms: &Query<&BoneRestGlobalTransform>,
+) -> Option<GlobalTransform> {
+    let head_gtf = global_transforms.get(head.0).ok()?;
+    let head_rest_gtf = rest_global_transforms.get(head.0).ok()?;
+    let offset = Vec3::from(properties.offset_from_head_bone);
+    Some(GlobalTransform::from(
+        Transform::from_translation(head_gtf.transform_point(offset))
+            .with_rotation(head_gtf.rotation() * head_rest_gtf.rotation().inverse()),
+    ))
+}
+
+fn reset_look_at_expressions(
+    trigger: Trigger<OnRemove, LookAt>,
+    mut weights: Query<&mut LookAtExpressionWeights>,
+) {
+    if let Ok(mut weights) = weights.get_mut(trigger.target()) {
+        weights.set_if_neq(LookAtExpressionWeights::default());
+    }
+}
+
+fn is_target_moved(
+    look_at: &LookAt,
+    mouse_moved: bool,
+    moved: &Query<(), Changed<GlobalTransform>>,
+    cameras: &Query<(Entity, &Camera)>,
+) -> bool {
+    match look_at {
+        LookAt::Cursor { camera } => {
+            mouse_moved
+                || match camera {
+                    Some(camera_entity) => moved.contains(*camera_entity),
+                    None => cameras.iter().any(|(entity, _)| moved.contains(entity)),
+                }
+        }
+        LookAt::Target(target_entity) => moved.contains(*target_entity),
+        LookAt::Position(_) => false,
+    }
 }
 
 fn calc_target_position(
     look_at: &LookAt,
     vrm_entity: Entity,
-    transforms: &Query<&Transform>,
     global_transforms: &Query<&GlobalTransform>,
     cameras: &Query<(Entity, &Camera)>,
     windows: &Query<(&Window, Has<PrimaryWindow>)>,
@@ -152,7 +439,11 @@ fn calc_target_position(
                 )
             }),
         },
-        LookAt::Target(target_entity) => transforms.get(*target_entity).map(|t| t.translation).ok(),
+        LookAt::Target(target_entity) => global_transforms
+            .get(*target_entity)
+            .map(GlobalTransform::translation)
+            .ok(),
+        LookAt::Position(position) => Some(*position),
     }
 }
 
@@ -216,15 +507,16 @@ fn calc_yaw_pitch(
         .compute_matrix()
         .inverse()
         .transform_point3(target);
+    calc_direction_yaw_pitch(local_target)
+}
 
-    let z = local_target.dot(Vec3::Z);
-    let x = local_target.dot(Vec3::X);
-    let yaw = (x.atan2(z)).to_degrees();
-
-    let xz = (x * x + z * z).sqrt();
-    let y = local_target.dot(Vec3::Y);
-    let pitch = (-y.atan2(xz)).to_degrees();
-
+/// Returns the yaw and the pitch in degrees that turn `+Z` to the direction.
+///
+/// Positive yaw turns toward `+X`, and positive pitch turns downward.
+fn calc_direction_yaw_pitch(direction: Vec3) -> (f32, f32) {
+    let yaw = direction.x.atan2(direction.z).to_degrees();
+    let xz = direction.x.hypot(direction.z);
+    let pitch = (-direction.y.atan2(xz)).to_degrees();
     (yaw, pitch)
 }
 
@@ -311,3 +603,288 @@ fn apply_right_eye_bone(
         0.0,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::success;
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::look_at::head::apply_head_look_at;
+    use crate::vrm::look_at::{
+        calc_direction_yaw_pitch, calc_look_at_space, calc_target_position, calc_yaw_pitch,
+        track_looking_target, LookAtExpressionWeights, VrmaLookAtEyes,
+    };
+    use crate::vrm::{BoneRestGlobalTransform, BoneRestTransform};
+    use bevy::ecs::system::RunSystemOnce;
+    use bevy::input::mouse::MouseMotion;
+    use bevy::prelude::*;
+    use bevy::window::PrimaryWindow;
+
+    #[test]
+    fn map_expression_weights() {
+        let range_map = RangeMap {
+            input_max_value: 20.0,
+            output_scale: 1.0,
+        };
+        let properties = LookAtProperties {
+            offset_from_head_bone: [0.0; 3],
+            range_map_horizontal_inner: range_map,
+            range_map_horizontal_outer: range_map,
+            range_map_vertical_down: range_map,
+            range_map_vertical_up: range_map,
+            r#type: LookAtType::Expression,
+        };
+
+        let weights = LookAtExpressionWeights::new(&properties, 10.0, -40.0);
+        assert_eq!(weights.look_left, 0.5);
+        assert_eq!(weights.look_right, 0.0);
+        assert_eq!(weights.look_up, 1.0);
+        assert_eq!(weights.look_down, 0.0);
+
+        let weights = LookAtExpressionWeights::new(&properties, -5.0, 5.0);
+        assert_eq!(weights.look_left, 0.0);
+        assert_eq!(weights.look_right, 0.25);
+        assert_eq!(weights.look_down, 0.25);
+    }
+
+    #[test]
+    fn yaw_pitch_from_look_at_space() -> TestResult {
+        let mut app = test_app();
+        let head = app
+            .world_mut()
+            .spawn((
+                GlobalTransform::from_xyz(0.0, 1.0, 0.0),
+                BoneRestGlobalTransform(GlobalTransform::from_xyz(0.0, 1.0, 0.0)),
+            ))
+            .id();
+        let properties = bone_properties([0.0, 0.1, 0.0]);
+
+        // Looking toward `+X` from between the eyes turns left.
+        let (yaw, pitch) =
+            look_at_space_yaw_pitch(&mut app, head, &properties, Vec3::new(1.0, 1.1, 1.0))?;
+        assert!((yaw - 45.0).abs() < 1e-3);
+        assert!(pitch.abs() < 1e-3);
+
+        let (yaw, pitch) =
+            look_at_space_yaw_pitch(&mut app, head, &properties, Vec3::new(0.0, 0.1, 1.0))?;
+        assert!(yaw.abs() < 1e-3);
+        assert!((pitch - 45.0).abs() < 1e-3);
+        success!()
+    }
+
+    #[test]
+    fn head_and_eyes_share_target_angle() -> TestResult {
+        let mut app = test_app();
+        app.add_plugins(TransformPlugin);
+        let hips_tf = Transform::from_xyz(0.0, 1.0, 0.0);
+        let head_tf = Transform::from_xyz(0.0, 0.5, 0.0);
+        let hips = app
+            .world_mut()
+            .spawn((
+                hips_tf,
+                BoneRestTransform(hips_tf),
+                BoneRestGlobalTransform(GlobalTransform::from(hips_tf)),
+            ))
+            .id();
+        let head = app
+            .world_mut()
+            .spawn((
+                head_tf,
+                BoneRestTransform(head_tf),
+                BoneRestGlobalTransform(GlobalTransform::from_xyz(0.0, 1.5, 0.0)),
+                ChildOf(hips),
+            ))
+            .id();
+        let target = Vec3::new(1.0, 1.5, 1.0);
+        let no_share = HeadLookAtBone::new(0.0, 0.0, 0.0);
+        app.world_mut().spawn((
+            LookAt::Position(target),
+            HeadLookAt {
+                head: HeadLookAtBone::new(0.5, 90.0, 90.0),
+                neck: no_share,
+                chest: no_share,
+                spine: no_share,
+                damping: 0.0,
+                ..default()
+            },
+            HeadBoneEntity(head),
+            HipsBoneEntity(hips),
+        ));
+        app.update();
+        app.world_mut().run_system_once(apply_head_look_at)?;
+        app.update();
+
+        let head_gtf = app
+            .world()
+            .get::<GlobalTransform>(head)
+            .ok_or("head is not found")?;
+        let (head_yaw, _) = calc_direction_yaw_pitch(head_gtf.rotation() * Vec3::Z);
+        let (eye_yaw, eye_pitch) =
+            look_at_space_yaw_pitch(&mut app, head, &bone_properties([0.0; 3]), target)?;
+        assert!((head_yaw - 22.5).abs() < 1e-3);
+        assert!((head_yaw + eye_yaw - 45.0).abs() < 1e-3);
+        assert!(eye_pitch.abs() < 1e-3);
+        success!()
+    }
+
+    #[test]
+    fn rest_eyes_when_vrma_stops() -> TestResult {
+        let mut app = test_app();
+        app.init_resource::<LookAtUpdateMode>()
+            .init_asset::<AnimationClip>()
+            .add_event::<MouseMotion>();
+        let head = app
+            .world_mut()
+            .spawn((Transform::default(), GlobalTransform::default()))
+            .id();
+        let rest = Transform::from_xyz(0.03, 0.0, 0.0);
+        let mut spawn_eye = || {
+            app.world_mut()
+                .spawn((
+                    rest.with_rotation(Quat::from_rotation_y(0.3)),
+                    BoneRestTransform(rest),
+                ))
+                .id()
+        };
+        let (left_eye, right_eye) = (spawn_eye(), spawn_eye());
+        let vrm = app
+            .world_mut()
+            .spawn((
+                bone_properties([0.0; 3]),
+                HeadBoneEntity(head),
+                LeftEyeBoneEntity(left_eye),
+                RightEyeBoneEntity(right_eye),
+                VrmaLookAtEyes,
+            ))
+            .id();
+
+        app.world_mut().run_system_once(track_looking_target)?;
+        for eye in [left_eye, right_eye] {
+            assert_eq!(app.world().get::<Transform>(eye), Some(&rest));
+        }
+        assert!(!app.world().entity(vrm).contains::<VrmaLookAtEyes>());
+        success!()
+    }
+
+    #[test]
+    fn evaluate_on_change_only_when_target_moves() -> TestResult {
+        let mut app = test_app();
+        app.insert_resource(LookAtUpdateMode::OnChange)
+            .init_asset::<AnimationClip>()
+            .add_event::<MouseMotion>()
+            .add_systems(Update, track_looking_target);
+        let head = app
+            .world_mut()
+            .spawn((
+                GlobalTransform::default(),
+                BoneRestGlobalTransform::default(),
+            ))
+            .id();
+        let eye = app.world_mut().spawn(Transform::default()).id();
+        let target = app
+            .world_mut()
+            .spawn(GlobalTransform::from_xyz(1.0, 0.0, 1.0))
+            .id();
+        let mut properties = bone_properties([0.0; 3]);
+        properties.r#type = LookAtType::Expression;
+        let vrm = app
+            .world_mut()
+            .spawn((
+                LookAt::Target(target),
+                properties,
+                HeadBoneEntity(head),
+                LeftEyeBoneEntity(eye),
+                RightEyeBoneEntity(eye),
+                LookAtExpressionWeights::default(),
+            ))
+            .id();
+        let weights = |app: &mut App| -> TestResult<LookAtExpressionWeights> {
+            app.update();
+            let mut vrm = app.world_mut().entity_mut(vrm);
+            let mut weights = vrm
+                .get_mut::<LookAtExpressionWeights>()
+                .ok_or("weights are not found")?;
+            // Clears the weights to see whether the next update writes them.
+            Ok(std::mem::take(&mut *weights))
+        };
+
+        assert!(0.0 < weights(&mut app)?.look_left);
+        assert_eq!(weights(&mut app)?, LookAtExpressionWeights::default());
+
+        app.world_mut()
+            .entity_mut(target)
+            .insert(GlobalTransform::from_xyz(-1.0, 0.0, 1.0));
+        assert!(0.0 < weights(&mut app)?.look_right);
+        success!()
+    }
+
+    #[test]
+    fn look_at_parented_target() -> TestResult {
+        let mut app = test_app();
+        app.add_plugins(TransformPlugin);
+        let target = app
+            .world_mut()
+            .spawn(Transform::from_xyz(1.0, 2.0, 3.0))
+            .with_child(Transform::from_xyz(0.0, 1.0, 0.0))
+            .id();
+        let child = app
+            .world()
+            .get::<Children>(target)
+            .and_then(|children| children.first().copied())
+            .ok_or("child is not found")?;
+        app.update();
+
+        let position = app.world_mut().run_system_once(
+            move |global_transforms: Query<&GlobalTransform>,
+                  cameras: Query<(Entity, &Camera)>,
+                  windows: Query<(&Window, Has<PrimaryWindow>)>| {
+                calc_target_position(
+                    &LookAt::Target(child),
+                    child,
+                    &global_transforms,
+                    &cameras,
+                    &windows,
+                )
+            },
+        )?;
+        assert_eq!(position, Some(Vec3::new(1.0, 3.0, 3.0)));
+        success!()
+    }
+
+    fn look_at_space_yaw_pitch(
+        app: &mut App,
+        head: Entity,
+        properties: &LookAtProperties,
+        target: Vec3,
+    ) -> TestResult<(f32, f32)> {
+        let properties = properties.clone();
+        let look_at_space = app.world_mut().run_system_once(
+            move |global_transforms: Query<&GlobalTransform>,
+                  rest_global_transforms: Query<&BoneRestGlobalTransform>| {
+                calc_look_at_space(
+                    &properties,
+                    &HeadBoneEntity(head),
+                    &global_transforms,
+                    &rest_global_transforms,
+                )
+            },
+        )?;
+        let look_at_space = look_at_space.ok_or("look-at space is not found")?;
+        Ok(calc_yaw_pitch(&look_at_space, target))
+    }
+
+    fn bone_properties(offset_from_head_bone: [f32; 3]) -> LookAtProperties {
+        let range_map = RangeMap {
+            input_max_value: 90.0,
+            output_scale: 90.0,
+        };
+        LookAtProperties {
+            offset_from_head_bone,
+            range_map_horizontal_inner: range_map,
+            range_map_horizontal_outer: range_map,
+            range_map_vertical_down: range_map,
+            range_map_vertical_up: range_map,
+            r#type: LookAtType::Bone,
+        }
+    }
+}
@@ -0,0 +1,409 @@
+//! This module makes the eye movement of [`LookAt`] natural with [`GazeBehavior`].
+
+use crate::prelude::*;
+use crate::vrm::expressions::{random, seed_random, AutoBlinkState};
+use bevy::prelude::*;
+
+/// A candidate that [`GazeBehavior`] pays attention to.
+#[derive(Reflect, Debug, Copy, Clone, PartialEq)]
+pub struct AttentionTarget {
+    /// The target to look at.
+    ///
+    /// To look at another avatar's face, specify its [`HeadBoneEntity`] with [`LookAt::Target`].
+    pub look_at: LookAt,
+    /// The relative probability of choosing this target.
+    pub weight: f32,
+    /// The minimum seconds to keep looking at this target.
+    pub min_dwell: f32,
+    /// The maximum seconds to keep looking at this target.
+    pub max_dwell: f32,
+}
+
+impl AttentionTarget {
+    pub const fn new(
+        look_at: LookAt,
+        weight: f32,
+    ) -> Self {
+        Self {
+            look_at,
+            weight,
+            min_dwell: 1.5,
+            max_dwell: 4.0,
+        }
+    }
+
+    /// Sets the range of seconds to keep looking at this target.
+    pub const fn with_dwell(
+        mut self,
+        min_dwell: f32,
+        max_dwell: f32,
+    ) -> Self {
+        self.min_dwell = min_dwell;
+        self.max_dwell = max_dwell;
+        self
+    }
+}
+
+/// Adds natural behaviour to the eye movement of [`LookAt`].
+///
+/// - The eyes follow the target with critically damped smoothing instead of snapping to it.
+/// - While fixating, the eyes make small random jumps called micro-saccades.
+/// - A large gaze shift triggers a blink of [`AutoBlink`], if the VRM has it.
+/// - If [`GazeBehavior::attention`] is not empty, [`LookAt`] switches between the candidates by their weights.
+///
+/// The gaze is evaluated every frame while this component exists, regardless of [`LookAtUpdateMode`].
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn spawn(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     let camera = commands.spawn(Camera3d::default()).id();
+///     commands.spawn((
+///         VrmHandle(asset_server.load("vrm/AliciaSolid.vrm")),
+///         AutoBlink::default(),
+///         GazeBehavior {
+///             attention: vec![
+///                 AttentionTarget::new(LookAt::Target(camera), 3.0),
+///                 AttentionTarget::new(LookAt::Position(Vec3::new(1.0, 1.5, 2.0)), 1.0)
+///                     .with_dwell(0.5, 1.0),
+///             ],
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+#[require(GazeState)]
+pub struct GazeBehavior {
+    /// The approximate seconds for the eyes to reach the target; `0.0` snaps to it.
+    pub smooth_time: f32,
+    /// The maximum angle of micro-saccades in degrees; `0.0` disables them.
+    pub saccade_amplitude: f32,
+    /// The minimum seconds between micro-saccades.
+    pub min_saccade_interval: f32,
+    /// The maximum seconds between micro-saccades.
+    pub max_saccade_interval: f32,
+    /// The gaze shift in degrees that triggers a blink; `None` disables it.
+    pub blink_threshold: Option<f32>,
+    /// The candidates to look at; if empty, [`LookAt`] is not changed.
+    pub attention: Vec<AttentionTarget>,
+}
+
+impl Default for GazeBehavior {
+    fn default() -> Self {
+        Self {
+            smooth_time: 0.06,
+            saccade_amplitude: 0.6,
+            min_saccade_interval: 0.3,
+            max_saccade_interval: 1.5,
+            blink_threshold: Some(25.0),
+            attention: Vec::new(),
+        }
+    }
+}
+
+#[derive(Component, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub(crate) struct GazeState {
+    /// The yaw and the pitch of the target in degrees; `None` before the first update.
+    target: Option<Vec2>,
+    /// The smoothed yaw and pitch in degrees.
+    gaze: Vec2,
+    velocity: Vec2,
+    saccade: Vec2,
+    saccade_remaining: f32,
+    blink_requested: bool,
+    attention: Option<usize>,
+    dwell_remaining: f32,
+    rng: u64,
+}
+
+impl GazeState {
+    /// The angle in degrees within which the eyes are considered fixating on the target.
+    const FIXATION_ANGLE: f32 = 1.0;
+
+    fn random(&mut self) -> f32 {
+        random(&mut self.rng)
+    }
+
+    /// Seeds the random numbers with the entity of the VRM, unless already seeded.
+    pub(crate) fn seed(
+        &mut self,
+        vrm: Entity,
+    ) {
+        if self.rng == 0 {
+            self.rng = seed_random(vrm.to_bits());
+        }
+    }
+
+    /// Returns the yaw and the pitch in degrees that the eyes rotate this frame.
+    pub(crate) fn update(
+        &mut self,
+        behavior: &GazeBehavior,
+        yaw: f32,
+        pitch: f32,
+        delta: f32,
+    ) -> (f32, f32) {
+        let target = Vec2::new(yaw, pitch);
+        let Some(previous) = self.target.replace(target) else {
+            self.gaze = target;
+            return (yaw, pitch);
+        };
+        if behavior
+            .blink_threshold
+            .is_some_and(|threshold| threshold < angle_difference(previous, target).length())
+        {
+            self.blink_requested = true;
+        }
+        // The yaw wraps at ±180 degrees, so the eyes turn the shorter way.
+        let shortest_target = self.gaze + angle_difference(self.gaze, target);
+        self.gaze = Vec2::new(
+            wrap_degrees(smooth_damp(
+                self.gaze.x,
+                shortest_target.x,
+                &mut self.velocity.x,
+                behavior.smooth_time,
+                delta,
+            )),
+            smooth_damp(
+                self.gaze.y,
+                target.y,
+                &mut self.velocity.y,
+                behavior.smooth_time,
+                delta,
+            ),
+        );
+
+        if behavior.saccade_amplitude <= 0.0
+            || Self::FIXATION_ANGLE < angle_difference(self.gaze, target).length()
+        {
+            self.saccade = Vec2::ZERO;
+        } else {
+            self.saccade_remaining -= delta;
+            if self.saccade_remaining <= 0.0 {
+                let angle = self.random() * std::f32::consts::TAU;
+                let radius = behavior.saccade_amplitude * self.random().sqrt();
+                self.saccade = Vec2::from_angle(angle) * radius;
+                let range =
+                    (behavior.max_saccade_interval - behavior.min_saccade_interval).max(0.0);
+                self.saccade_remaining = behavior.min_saccade_interval + range * self.random();
+            }
+        }
+        let gaze = self.gaze + self.saccade;
+        (gaze.x, gaze.y)
+    }
+
+    /// Returns the index of the next attention target, or `None` if the current one should be kept.
+    fn choose_attention(
+        &mut self,
+        attention: &[AttentionTarget],
+        is_available: impl Fn(&AttentionTarget) -> bool,
+        delta: f32,
+    ) -> Option<usize> {
+        self.dwell_remaining -= delta;
+        let current = self
+            .attention
+            .filter(|i| attention.get(*i).is_some_and(&is_available));
+        if current.is_some() && 0.0 < self.dwell_remaining {
+            return None;
+        }
+        let candidates = attention
+            .iter()
+            .enumerate()
+            .filter(|(_, target)| 0.0 < target.weight && is_available(target))
+            .collect::<Vec<_>>();
+        // Switch to another target if there is one.
+        let candidates = if 1 < candidates.len() {
+            candidates
+                .into_iter()
+                .filter(|(i, _)| Some(*i) != current)
+                .collect()
+        } else {
+            candidates
+        };
+        let total = candidates
+            .iter()
+            .map(|(_, target)| target.weight)
+            .sum::<f32>();
+        let mut threshold = self.random() * total;
+        let (index, target) = candidates
+            .iter()
+            .find(|(_, target)| {
+                threshold -= target.weight;
+                threshold <= 0.0
+            })
+            .or(candidates.last())
+            .copied()?;
+        let range = (target.max_dwell - target.min_dwell).max(0.0);
+        self.dwell_remaining = target.min_dwell + range * self.random();
+        self.attention = Some(index);
+        Some(index)
+    }
+}
+
+/// Returns the yaw and the pitch in degrees from `from` to `to`, taking the shorter way around for the yaw.
+fn angle_difference(
+    from: Vec2,
+    to: Vec2,
+) -> Vec2 {
+    let difference = to - from;
+    Vec2::new(wrap_degrees(difference.x), difference.y)
+}
+
+/// Wraps the angle in degrees into the range of `-180.0` to `180.0`.
+fn wrap_degrees(degrees: f32) -> f32 {
+    (degrees + 180.0).rem_euclid(360.0) - 180.0
+}
+
+/// Moves `current` toward `target` like a critically damped spring.
+fn smooth_damp(
+    current: f32,
+    target: f32,
+    velocity: &mut f32,
+    smooth_time: f32,
+    delta: f32,
+) -> f32 {
+    if smooth_time <= 0.0 {
+        *velocity = 0.0;
+        return target;
+    }
+    let omega = 2.0 / smooth_time;
+    let x = omega * delta;
+    let exp = 1.0 / (1.0 + x + 0.48 * x * x + 0.235 * x * x * x);
+    let change = current - target;
+    let temp = (*velocity + omega * change) * delta;
+    *velocity = (*velocity - omega * temp) * exp;
+    target + (change + temp) * exp
+}
+
+pub(super) fn update_attention(
+    mut commands: Commands,
+    mut vrms: Query<(Entity, &GazeBehavior, &mut GazeState, Option<&mut LookAt>)>,
+    entities: Query<(), With<GlobalTransform>>,
+    time: Res<Time>,
+) {
+    for (vrm, behavior, mut state, look_at) in vrms.iter_mut() {
+        if behavior.attention.is_empty() {
+            continue;
+        }
+        state.seed(vrm);
+        let is_available = |target: &AttentionTarget| match target.look_at {
+            LookAt::Target(entity) => entities.contains(entity),
+            LookAt::Cursor { .. } | LookAt::Position(_) => true,
+        };
+        let Some(index) =
+            state.choose_attention(&behavior.attention, is_available, time.delta_secs())
+        else {
+            continue;
+        };
+        let next = behavior.attention[index].look_at;
+        match look_at {
+            Some(mut look_at) => {
+                look_at.set_if_neq(next);
+            }
+            None => {
+                commands.entity(vrm).insert(next);
+            }
+        }
+    }
+}
+
+pub(super) fn blink_on_gaze_shift(mut vrms: Query<(&mut GazeState, Option<&mut AutoBlinkState>)>) {
+    for (mut state, blink) in vrms.iter_mut() {
+        if !state.blink_requested {
+            continue;
+        }
+        state.blink_requested = false;
+        if let Some(mut blink) = blink {
+            blink.request_blink();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::vrm::look_at::gaze::{GazeBehavior, GazeState};
+    use bevy::prelude::*;
+
+    #[test]
+    fn smooth_gaze_and_blink_on_shift() {
+        let behavior = GazeBehavior {
+            saccade_amplitude: 0.0,
+            ..default()
+        };
+        let mut state = GazeState::default();
+        assert_eq!(state.update(&behavior, 0.0, 0.0, 0.016), (0.0, 0.0));
+
+        let (yaw, _) = state.update(&behavior, 40.0, 0.0, 0.016);
+        assert!(0.0 < yaw && yaw < 40.0);
+        assert!(state.blink_requested);
+
+        for _ in 0..60 {
+            state.update(&behavior, 40.0, 0.0, 0.016);
+        }
+        let (yaw, pitch) = state.update(&behavior, 40.0, 0.0, 0.016);
+        assert!((yaw - 40.0).abs() < 0.01 && pitch.abs() < 0.01);
+    }
+
+    #[test]
+    fn turn_the_shorter_way_across_behind() {
+        let behavior = GazeBehavior {
+            saccade_amplitude: 0.0,
+            blink_threshold: Some(25.0),
+            ..default()
+        };
+        let mut state = GazeState::default();
+        state.update(&behavior, 170.0, 0.0, 0.016);
+
+        let (yaw, _) = state.update(&behavior, -170.0, 0.0, 0.016);
+        assert!(170.0 < yaw.abs());
+        assert!(!state.blink_requested);
+
+        for _ in 0..60 {
+            state.update(&behavior, -170.0, 0.0, 0.016);
+        }
+        let (yaw, _) = state.update(&behavior, -170.0, 0.0, 0.016);
+        assert!((yaw + 170.0).abs() < 0.01);
+    }
+
+    #[test]
+    fn micro_saccades_while_fixating() {
+        let behavior = GazeBehavior::default();
+        let mut state = GazeState {
+            rng: 1,
+            ..default()
+        };
+        state.update(&behavior, 10.0, 0.0, 0.016);
+        let (yaw, pitch) = state.update(&behavior, 10.0, 0.0, 0.016);
+        let offset = Vec2::new(yaw - 10.0, pitch).length();
+        assert!(0.0 < offset && offset <= behavior.saccade_amplitude);
+    }
+
+    #[test]
+    fn switch_attention_targets() {
+        let attention = [
+            AttentionTarget::new(LookAt::Position(Vec3::X), 1.0).with_dwell(1.0, 1.0),
+            AttentionTarget::new(LookAt::Position(Vec3::Y), 1.0).with_dwell(1.0, 1.0),
+            AttentionTarget::new(LookAt::Position(Vec3::Z), 0.0),
+        ];
+        let mut state = GazeState {
+            rng: 1,
+            ..default()
+        };
+        let first = state
+            .choose_attention(&attention, |_| true, 0.016)
+            .expect("Failed to choose a target");
+        assert!(first < 2);
+        assert_eq!(state.choose_attention(&attention, |_| true, 0.5), None);
+        assert_eq!(
+            state.choose_attention(&attention, |_| true, 0.6),
+            Some(1 - first)
+        );
+    }
+}
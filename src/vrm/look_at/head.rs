@@ -0,0 +1,299 @@
+//! This module turns the head, the neck and the spine toward the target of [`LookAt`] with [`HeadLookAt`].
+
+use crate::prelude::*;
+use crate::vrm::look_at::{calc_direction_yaw_pitch, calc_target_position};
+use crate::vrm::{BoneRestGlobalTransform, BoneRestTransform};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::window::PrimaryWindow;
+
+/// The share of a bone in [`HeadLookAt`].
+#[derive(Reflect, Debug, Copy, Clone, PartialEq)]
+pub struct HeadLookAtBone {
+    /// The ratio of the yaw and the pitch to the target that this bone rotates.
+    pub weight: f32,
+    /// The maximum yaw of this bone in degrees.
+    pub max_yaw: f32,
+    /// The maximum pitch of this bone in degrees.
+    pub max_pitch: f32,
+}
+
+impl HeadLookAtBone {
+    pub const fn new(
+        weight: f32,
+        max_yaw: f32,
+        max_pitch: f32,
+    ) -> Self {
+        Self {
+            weight,
+            max_yaw,
+            max_pitch,
+        }
+    }
+
+    /// Returns the yaw and the pitch of this bone in degrees.
+    fn distribute(
+        &self,
+        yaw: f32,
+        pitch: f32,
+    ) -> (f32, f32) {
+        (
+            (yaw * self.weight).clamp(-self.max_yaw.abs(), self.max_yaw.abs()),
+            (pitch * self.weight).clamp(-self.max_pitch.abs(), self.max_pitch.abs()),
+        )
+    }
+}
+
+/// Turns the head, the neck, the chest and the spine toward the target of [`LookAt`].
+///
+/// The rotations are added on top of the pose of the playing VRMA.
+/// Since the eyes look at the target relative to the head,
+/// they cover the rest of the angle that the bones do not rotate.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn spawn(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+/// ) {
+///     let camera = commands.spawn(Camera3d::default()).id();
+///     commands.spawn((
+///         VrmHandle(asset_server.load("vrm/AliciaSolid.vrm")),
+///         LookAt::Cursor {
+///             camera: Some(camera),
+///         },
+///         HeadLookAt {
+///             damping: 0.3,
+///             ..default()
+///         },
+///     ));
+/// }
+/// ```
+#[derive(Component, Reflect, Debug, Clone, PartialEq)]
+#[reflect(Component, Default)]
+#[require(HeadLookAtState)]
+pub struct HeadLookAt {
+    /// The blend weight of the whole layer, in the range of `0.0` to `1.0`.
+    pub weight: f32,
+    pub head: HeadLookAtBone,
+    pub neck: HeadLookAtBone,
+    /// Applied to `upperChest`, or `chest` if the VRM does not have it.
+    pub chest: HeadLookAtBone,
+    pub spine: HeadLookAtBone,
+    /// The seconds it takes to follow about 63% of a change of the target; `0.0` follows immediately.
+    pub damping: f32,
+}
+
+impl Default for HeadLookAt {
+    fn default() -> Self {
+        Self {
+            weight: 1.0,
+            head: HeadLookAtBone::new(0.35, 40.0, 30.0),
+            neck: HeadLookAtBone::new(0.25, 30.0, 20.0),
+            chest: HeadLookAtBone::new(0.1, 15.0, 10.0),
+            spine: HeadLookAtBone::new(0.05, 10.0, 8.0),
+            damping: 0.15,
+        }
+    }
+}
+
+#[derive(Reflect, Debug, Copy, Clone, PartialEq)]
+struct AppliedRotation {
+    /// The rotation of the pose before this layer is applied.
+    base: Quat,
+    /// The rotation written by this layer; if the transform still has it, the pose was not animated since.
+    written: Quat,
+}
+
+#[derive(Component, Reflect, Debug, Default, Clone, PartialEq)]
+#[reflect(Component)]
+pub(crate) struct HeadLookAtState {
+    /// The damped yaw in degrees.
+    yaw: f32,
+    /// The damped pitch in degrees.
+    pitch: f32,
+    applied: HashMap<Entity, AppliedRotation>,
+}
+
+impl HeadLookAtState {
+    fn damp(
+        &mut self,
+        yaw: f32,
+        pitch: f32,
+        damping: f32,
+        delta: f32,
+    ) {
+        let t = if damping <= 0.0 {
+            1.0
+        } else {
+            1.0 - (-delta / damping).exp()
+        };
+        self.yaw = self.yaw.lerp(yaw, t);
+        self.pitch = self.pitch.lerp(pitch, t);
+    }
+
+    /// Forgets the rotations of bones that are no longer the targets,
+    /// such as the bones of the scene replaced by a hot reload.
+    fn retain_bones(
+        &mut self,
+        bones: &[Option<Entity>],
+    ) {
+        self.applied.retain(|bone, _| bones.contains(&Some(*bone)));
+    }
+}
+
+pub(super) fn apply_head_look_at(
+    mut vrms: Query<(
+        &HeadLookAt,
+        &mut HeadLookAtState,
+        Option<&LookAt>,
+        &HeadBoneEntity,
+        Option<&NeckBoneEntity>,
+        Option<&UpperChestBoneEntity>,
+        Option<&ChestBoneEntity>,
+        Option<&SpineBoneEntity>,
+        Option<&HipsBoneEntity>,
+    )>,
+    mut bones: Query<(&mut Transform, &BoneRestTransform, &BoneRestGlobalTransform)>,
+    cameras: Query<(Entity, &Camera)>,
+    global_transforms: Query<&GlobalTransform>,
+    windows: Query<(&Window, Has<PrimaryWindow>)>,
+    time: Res<Time>,
+) {
+    for (head_look_at, mut state, look_at, head, neck, upper_chest, chest, spine, hips) in
+        vrms.iter_mut()
+    {
+        // The target angles are measured in the normalized space of the hips,
+        // where the model faces `+Z` in the rest pose.
+        let hips_rotation = hips
+            .and_then(|hips| {
+                let gtf = global_transforms.get(hips.0).ok()?;
+                let (_, _, rest_g) = bones.get(hips.0).ok()?;
+                Some(gtf.rotation() * rest_g.0.rotation().inverse())
+            })
+            .unwrap_or_default();
+        let (yaw, pitch) = look_at
+            .and_then(|look_at| {
+                let head_position = global_transforms.get(head.0).ok()?.translation();
+                let target =
+                    calc_target_position(look_at, head.0, &global_transforms, &cameras, &windows)?;
+                let direction = hips_rotation.inverse() * (target - head_position);
+                Some(calc_direction_yaw_pitch(direction))
+            })
+            .unwrap_or_default();
+        state.damp(yaw, pitch, head_look_at.damping, time.delta_secs());
+
+        let weight = head_look_at.weight.clamp(0.0, 1.0);
+        let (yaw, pitch) = (state.yaw * weight, state.pitch * weight);
+        let chest = upper_chest.map(|e| e.0).or(chest.map(|e| e.0));
+        let targets = [
+            (spine.map(|e| e.0), head_look_at.spine),
+            (chest, head_look_at.chest),
+            (neck.map(|e| e.0), head_look_at.neck),
+            (Some(head.0), head_look_at.head),
+        ];
+        state.retain_bones(&targets.map(|(bone, _)| bone));
+        for (bone, share) in targets {
+            let Some(bone) = bone else {
+                continue;
+            };
+            let Ok((mut tf, rest, rest_g)) = bones.get_mut(bone) else {
+                continue;
+            };
+            let base = match state.applied.get(&bone) {
+                Some(applied) if applied.written == tf.rotation => applied.base,
+                _ => tf.rotation,
+            };
+            let (bone_yaw, bone_pitch) = share.distribute(yaw, pitch);
+            let offset = Quat::from_euler(
+                EulerRot::YXZ,
+                bone_yaw.to_radians(),
+                bone_pitch.to_radians(),
+                0.0,
+            );
+            // Converts the rotation in the normalized space into the local space of the bone,
+            // in the same way as retargeting VRMA.
+            let to_local = rest.rotation * rest_g.rotation().inverse();
+            tf.rotation = to_local * offset * to_local.inverse() * base;
+            state.applied.insert(
+                bone,
+                AppliedRotation {
+                    base,
+                    written: tf.rotation,
+                },
+            );
+        }
+    }
+}
+
+pub(super) fn restore_head_look_at(
+    trigger: Trigger<OnRemove, HeadLookAt>,
+    mut states: Query<&mut HeadLookAtState>,
+    mut transforms: Query<&mut Transform>,
+) {
+    let Ok(mut state) = states.get_mut(trigger.target()) else {
+        return;
+    };
+    for (bone, applied) in state.applied.drain() {
+        if let Ok(mut tf) = transforms.get_mut(bone) {
+            if tf.rotation == applied.written {
+                tf.rotation = applied.base;
+            }
+        }
+    }
+    state.yaw = 0.0;
+    state.pitch = 0.0;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::look_at::calc_direction_yaw_pitch;
+    use crate::vrm::look_at::head::{AppliedRotation, HeadLookAt, HeadLookAtState};
+    use bevy::math::Vec3;
+    use bevy::prelude::{Entity, Quat};
+
+    #[test]
+    fn distribute_angles() {
+        let head_look_at = HeadLookAt::default();
+        assert_eq!(head_look_at.head.distribute(100.0, -10.0), (35.0, -3.5));
+        assert_eq!(head_look_at.spine.distribute(-400.0, 0.0), (-10.0, 0.0));
+
+        let mut state = HeadLookAtState::default();
+        state.damp(90.0, 0.0, 0.0, 0.016);
+        assert_eq!(state.yaw, 90.0);
+        state.damp(0.0, 0.0, 0.1, 0.1);
+        assert!((state.yaw - 90.0 * (-1.0_f32).exp()).abs() < 1e-3);
+    }
+
+    #[test]
+    fn yaw_pitch_rotates_forward_to_direction() {
+        let direction = Vec3::new(1.0, 1.0, 1.0).normalize();
+        let (yaw, pitch) = calc_direction_yaw_pitch(direction);
+        let rotation = Quat::from_euler(
+            bevy::math::EulerRot::YXZ,
+            yaw.to_radians(),
+            pitch.to_radians(),
+            0.0,
+        );
+        assert!((rotation * Vec3::Z).distance(direction) < 1e-5);
+    }
+
+    #[test]
+    fn forget_replaced_bones() {
+        let applied = AppliedRotation {
+            base: Quat::IDENTITY,
+            written: Quat::IDENTITY,
+        };
+        let old = Entity::from_raw(1);
+        let new = Entity::from_raw(2);
+        let mut state = HeadLookAtState::default();
+        state.applied.insert(old, applied);
+        state.applied.insert(new, applied);
+
+        state.retain_bones(&[None, Some(new)]);
+        assert!(!state.applied.contains_key(&old));
+        assert!(state.applied.contains_key(&new));
+    }
+}
@@ -0,0 +1,391 @@
+//! This module provides [`VrmMetaAsset`], which reads only the metadata of VRM,
+//! and [`VrmMeta`] with [`VrmUsagePolicy`] to honour the license of models.
+
+pub(crate) mod usage_policy;
+pub(crate) mod vrm_meta;
+
+use crate::error::{vrm_error, VrmError};
+use crate::vrm::gltf::extensions::vrm0::secondary_animation::append_virtual_tails;
+use crate::vrm::gltf::extensions::vrm0::{GltfDocument, Vrm0};
+use crate::vrm::gltf::extensions::vrmc_vrm::{Expressions, Meta};
+use crate::vrm::gltf::extensions::{is_vrm0, VRM0, VRMC_SPRING_BONE, VRMC_VRM};
+use crate::vrm::gltf::glb::{read_glb_bin_range, read_glb_json};
+use bevy::app::{App, Plugin};
+use bevy::asset::io::Reader;
+use bevy::asset::{Asset, AssetApp, AssetLoader, Handle, LoadContext, RenderAssetUsages};
+use bevy::image::{CompressedImageFormats, Image, ImageSampler, ImageType};
+use bevy::prelude::TypePath;
+use serde::{Deserialize, Serialize};
+use usage_policy::{VrmUsagePolicy, VrmUsagePolicyAction, VrmUsageViolation, VrmUsageViolations};
+use vrm_meta::{AvatarPermission, CommercialUsage, CreditNotation, Modification, VrmMeta};
+
+pub(super) struct VrmMetaPlugin;
+
+impl Plugin for VrmMetaPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.init_asset::<VrmMetaAsset>()
+            .register_asset_loader(VrmMetaLoader)
+            .init_resource::<VrmUsagePolicy>()
+            .register_type::<VrmMeta>()
+            .register_type::<AvatarPermission>()
+            .register_type::<CommercialUsage>()
+            .register_type::<CreditNotation>()
+            .register_type::<Modification>()
+            .register_type::<VrmUsagePolicy>()
+            .register_type::<VrmUsagePolicyAction>()
+            .register_type::<VrmUsageViolation>()
+            .register_type::<VrmUsageViolations>();
+    }
+}
+
+/// The metadata of VRM.
+///
+/// This asset is loaded from the same `.vrm` file as [`VrmAsset`](crate::prelude::VrmAsset),
+/// but only the glTF JSON and the thumbnail image are read;
+/// meshes, materials and other textures are not decoded.
+/// The extensions are not validated either, so models violating the specification can be listed,
+/// such as in an avatar picker.
+///
+/// The loader of this asset is not bound to the `vrm` extension, which belongs to [`VrmAsset`](crate::prelude::VrmAsset),
+/// so it must be loaded with the type specified as below.
+/// Untyped loads and `.meta` files of `.vrm` always use the loader of [`VrmAsset`](crate::prelude::VrmAsset).
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// fn load_meta(asset_server: Res<AssetServer>) {
+///     let _meta = asset_server.load::<VrmMetaAsset>("<vrm>.vrm");
+/// }
+/// ```
+#[derive(Debug, Asset, TypePath)]
+pub struct VrmMetaAsset {
+    /// The meta information, such as the name, authors, version and license.
+    ///
+    /// VRM 0.x meta is converted into the VRM 1.0 structure.
+    /// If the model has no meta, the most restrictive permissions are assumed.
+    ///
+    /// Pass it to [`VrmUsagePolicy::violations`] to check the license before loading the model.
+    pub meta: VrmMeta,
+    /// The decoded thumbnail image.
+    ///
+    /// Its asset usage is determined by [`VrmMetaLoaderSettings::thumbnail_asset_usage`].
+    pub thumbnail: Option<Handle<Image>>,
+    /// The spec version of `VRMC_vrm`, or of the `VRM` extension if the model is VRM 0.x.
+    pub spec_version: String,
+    /// The number of expressions, including the custom expressions.
+    pub expression_count: usize,
+    /// The number of humanoid bones.
+    pub bone_count: usize,
+    /// The number of spring chains.
+    pub spring_count: usize,
+}
+
+/// The settings to load [`VrmMetaAsset`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VrmMetaLoaderSettings {
+    /// The asset usage of the thumbnail image.
+    ///
+    /// Defaults to [`RenderAssetUsages::MAIN_WORLD`], so no GPU resource is created.
+    /// Add [`RenderAssetUsages::RENDER_WORLD`] to display the thumbnail, e.g. in UI.
+    pub thumbnail_asset_usage: RenderAssetUsages,
+}
+
+impl Default for VrmMetaLoaderSettings {
+    fn default() -> Self {
+        Self {
+            thumbnail_asset_usage: RenderAssetUsages::MAIN_WORLD,
+        }
+    }
+}
+
+struct VrmMetaLoader;
+
+impl AssetLoader for VrmMetaLoader {
+    type Asset = VrmMetaAsset;
+    type Settings = VrmMetaLoaderSettings;
+    type Error = VrmError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let (json, is_glb) = read_glb_json(reader).await?;
+        let mut root: serde_json::Value =
+            serde_json::from_slice(&json).map_err(|e| VrmError::InvalidGltf(e.to_string()))?;
+        // VRM 0.x springs are counted as the chains of the loaded model, which have the virtual tails.
+        if root
+            .get("extensions")
+            .and_then(serde_json::Value::as_object)
+            .is_some_and(is_vrm0)
+        {
+            append_virtual_tails(&mut root);
+        }
+        let document =
+            MetaDocument::deserialize(&root).map_err(|e| VrmError::InvalidGltf(e.to_string()))?;
+        let extensions = root
+            .get("extensions")
+            .and_then(serde_json::Value::as_object)
+            .ok_or(VrmError::MissingExtension(VRMC_VRM))?;
+        let summary = MetaSummary::new(extensions, &document.gltf)?;
+
+        let image = summary
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.thumbnail_image)
+            .and_then(|index| document.image_view(usize::try_from(index).ok()?));
+        let thumbnail = match image {
+            Some((offset, length, mime_type)) if is_glb => {
+                read_glb_bin_range(reader, offset, length)
+                    .await?
+                    .and_then(|bytes| {
+                        match Image::from_buffer(
+                            &bytes,
+                            ImageType::MimeType(mime_type),
+                            CompressedImageFormats::NONE,
+                            true,
+                            ImageSampler::Default,
+                            settings.thumbnail_asset_usage,
+                        ) {
+                            Ok(image) => {
+                                Some(load_context.add_labeled_asset("Thumbnail".to_string(), image))
+                            }
+                            Err(e) => {
+                                vrm_error!("Failed to decode the thumbnail", e);
+                                None
+                            }
+                        }
+                    })
+            }
+            _ => None,
+        };
+
+        Ok(VrmMetaAsset {
+            thumbnail,
+            spec_version: summary.spec_version,
+            expression_count: summary.expression_count,
+            bone_count: summary.bone_count,
+            spring_count: summary.spring_count,
+            meta: summary.meta.as_ref().map(VrmMeta::from).unwrap_or_default(),
+        })
+    }
+}
+
+/// The parts of the extensions that [`VrmMetaAsset`] holds.
+///
+/// Only the meta has to be well-formed; the other parts are only counted and not validated,
+/// so that models violating the specification can still be listed.
+struct MetaSummary {
+    meta: Option<Meta>,
+    spec_version: String,
+    expression_count: usize,
+    bone_count: usize,
+    spring_count: usize,
+}
+
+impl MetaSummary {
+    fn new(
+        json: &serde_json::Map<String, serde_json::Value>,
+        document: &GltfDocument,
+    ) -> Result<Self, VrmError> {
+        if is_vrm0(json) {
+            return Self::from_vrm0(json, document);
+        }
+        let vrmc_vrm = json
+            .get(VRMC_VRM)
+            .ok_or(VrmError::MissingExtension(VRMC_VRM))?;
+        let count = |pointer: &str| {
+            vrmc_vrm
+                .pointer(pointer)
+                .and_then(serde_json::Value::as_object)
+                .map(serde_json::Map::len)
+                .unwrap_or_default()
+        };
+        Ok(Self {
+            meta: parse_part(vrmc_vrm, VRMC_VRM, "meta")?,
+            spec_version: vrmc_vrm
+                .get("specVersion")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            expression_count: count("/expressions/preset") + count("/expressions/custom"),
+            bone_count: count("/humanoid/humanBones"),
+            spring_count: json
+                .get(VRMC_SPRING_BONE)
+                .and_then(|spring_bone| spring_bone.get("springs"))
+                .and_then(serde_json::Value::as_array)
+                .map(Vec::len)
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Converts only the parts of VRM 0.x that are summarized; malformed parts other than the meta are skipped.
+    fn from_vrm0(
+        json: &serde_json::Map<String, serde_json::Value>,
+        document: &GltfDocument,
+    ) -> Result<Self, VrmError> {
+        let vrm0 = json.get(VRM0).ok_or(VrmError::MissingExtension(VRM0))?;
+        let vrm0 = Vrm0 {
+            spec_version: parse_part(vrm0, VRM0, "specVersion").ok().flatten(),
+            meta: parse_part(vrm0, VRM0, "meta")?,
+            humanoid: parse_part(vrm0, VRM0, "humanoid")
+                .ok()
+                .flatten()
+                .unwrap_or_default(),
+            blend_shape_master: parse_part(vrm0, VRM0, "blendShapeMaster").ok().flatten(),
+            secondary_animation: parse_part(vrm0, VRM0, "secondaryAnimation").ok().flatten(),
+            ..Default::default()
+        };
+        let vrmc_vrm = vrm0.to_vrmc_vrm(document);
+        Ok(Self {
+            meta: vrmc_vrm.meta,
+            spec_version: vrmc_vrm.spec_version,
+            expression_count: vrmc_vrm
+                .expressions
+                .as_ref()
+                .map(Expressions::len)
+                .unwrap_or_default(),
+            bone_count: vrmc_vrm.humanoid.human_bones.len(),
+            spring_count: vrm0
+                .to_vrmc_spring_bone(document)
+                .map(|spring_bone| spring_bone.springs.len())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+/// Deserializes the property of the extension, returning `None` if it does not exist.
+fn parse_part<T: serde::de::DeserializeOwned>(
+    extension: &serde_json::Value,
+    extension_name: &'static str,
+    key: &str,
+) -> Result<Option<T>, VrmError> {
+    extension
+        .get(key)
+        .map(T::deserialize)
+        .transpose()
+        .map_err(|source| VrmError::InvalidExtension {
+            extension: extension_name,
+            source,
+        })
+}
+
+/// The parts of the glTF JSON that are needed to read the metadata.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct MetaDocument {
+    #[serde(flatten)]
+    gltf: GltfDocument,
+    images: Vec<GltfImage>,
+    #[serde(rename = "bufferViews")]
+    buffer_views: Vec<GltfBufferView>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct GltfImage {
+    #[serde(rename = "bufferView")]
+    buffer_view: Option<usize>,
+    #[serde(rename = "mimeType")]
+    mime_type: Option<String>,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct GltfBufferView {
+    #[serde(rename = "byteOffset")]
+    byte_offset: usize,
+    #[serde(rename = "byteLength")]
+    byte_length: usize,
+}
+
+impl MetaDocument {
+    /// Returns the range in the binary chunk and the mime type of the embedded image.
+    ///
+    /// Images referenced by URIs are not supported because `.vrm` files embed their images.
+    fn image_view(
+        &self,
+        index: usize,
+    ) -> Option<(usize, usize, &str)> {
+        let image = self.images.get(index)?;
+        let view = self.buffer_views.get(image.buffer_view?)?;
+        Some((
+            view.byte_offset,
+            view.byte_length,
+            image.mime_type.as_deref()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::vrm0::GltfDocument;
+    use crate::vrm::gltf::glb::{split_glb, GLB_CHUNK_BIN, GLB_CHUNK_JSON};
+    use crate::vrm::meta::{MetaDocument, MetaSummary};
+    use serde::Deserialize;
+
+    fn chunk(
+        chunk_type: u32,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let mut bytes = (data.len() as u32).to_le_bytes().to_vec();
+        bytes.extend(chunk_type.to_le_bytes());
+        bytes.extend(data);
+        bytes
+    }
+
+    #[test]
+    fn read_image_from_glb() -> TestResult {
+        let json = br#"{
+            "nodes": [{}],
+            "images": [{ "bufferView": 0, "mimeType": "image/png" }],
+            "bufferViews": [{ "buffer": 0, "byteOffset": 2, "byteLength": 3 }]
+        }"#;
+        let mut glb = b"glTF".to_vec();
+        glb.extend(2u32.to_le_bytes());
+        glb.extend(0u32.to_le_bytes());
+        glb.extend(chunk(GLB_CHUNK_JSON, json));
+        glb.extend(chunk(GLB_CHUNK_BIN, &[0, 1, 2, 3, 4, 5]));
+
+        let (json, bin) = split_glb(&glb)?;
+        let document =
+            MetaDocument::deserialize(&serde_json::from_slice::<serde_json::Value>(json)?)?;
+        assert_eq!(document.gltf.nodes.len(), 1);
+        assert_eq!(document.image_view(0), Some((2, 3, "image/png")));
+        assert_eq!(bin.and_then(|bin| bin.get(2..5)), Some(&[2u8, 3, 4][..]));
+        success!()
+    }
+
+    #[test]
+    fn summarize_without_validation() -> TestResult {
+        let json = serde_json::json!({
+            "VRMC_vrm": {
+                "specVersion": "1.0",
+                "meta": { "name": "Alice", "authors": ["Bob"], "licenseUrl": "https://vrm.dev/licenses/1.0/" },
+                "humanoid": { "humanBones": { "hips": { "node": 100 }, "head": "broken" } },
+                "expressions": { "preset": { "happy": {} }, "custom": { "blush": 1 } }
+            },
+            "VRMC_springBone": { "springs": [{}, {}] }
+        });
+        let summary = MetaSummary::new(
+            json.as_object().ok_or("no extensions")?,
+            &GltfDocument::default(),
+        )?;
+        assert_eq!(
+            summary.meta.and_then(|meta| meta.name),
+            Some("Alice".to_string())
+        );
+        assert_eq!(summary.spec_version, "1.0");
+        assert_eq!(summary.expression_count, 2);
+        assert_eq!(summary.bone_count, 2);
+        assert_eq!(summary.spring_count, 2);
+        success!()
+    }
+}
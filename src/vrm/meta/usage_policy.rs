@@ -0,0 +1,169 @@
+use crate::vrm::meta::vrm_meta::{AvatarPermission, CommercialUsage, Modification, VrmMeta};
+use bevy::prelude::*;
+
+/// Describes how the application uses avatars, so that models whose license forbids it can be refused or flagged.
+///
+/// Each field is a permission the application needs;
+/// a model violates the policy if its [`VrmMeta`] grants less than that.
+/// The default policy needs nothing, so every model is accepted.
+///
+/// ```no_run
+/// use bevy::prelude::*;
+/// use bevy_vrm1::prelude::*;
+///
+/// App::new()
+///     .add_plugins((DefaultPlugins, VrmPlugin))
+///     .insert_resource(VrmUsagePolicy {
+///         commercial_usage: CommercialUsage::Corporation,
+///         redistribution: true,
+///         action: VrmUsagePolicyAction::Refuse,
+///         ..default()
+///     });
+/// ```
+#[derive(Resource, Debug, Clone, PartialEq, Default, Reflect)]
+#[reflect(Resource, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub struct VrmUsagePolicy {
+    /// What to do with models that violate this policy.
+    pub action: VrmUsagePolicyAction,
+    /// The avatar permission the users of the application need.
+    ///
+    /// Set [`AvatarPermission::Everyone`] if anyone other than the author can use the avatar.
+    pub avatar_permission: AvatarPermission,
+    /// The commercial usage of the application.
+    pub commercial_usage: CommercialUsage,
+    /// The modification the application makes to models.
+    pub modification: Modification,
+    /// Whether the application uses avatars in excessively violent contexts.
+    pub excessively_violent_usage: bool,
+    /// Whether the application uses avatars in excessively sexual contexts.
+    pub excessively_sexual_usage: bool,
+    /// Whether the application uses avatars for political or religious purposes.
+    pub political_or_religious_usage: bool,
+    /// Whether the application uses avatars for antisocial or hate purposes.
+    pub antisocial_or_hate_usage: bool,
+    /// Whether the application redistributes models.
+    pub redistribution: bool,
+}
+
+/// What to do with a model that violates [`VrmUsagePolicy`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum VrmUsagePolicyAction {
+    /// The model is not spawned, and [`VrmLoadFailed`](crate::prelude::VrmLoadFailed) is triggered
+    /// with [`VrmError::UsageNotPermitted`](crate::prelude::VrmError::UsageNotPermitted).
+    #[default]
+    Refuse,
+    /// The model is spawned with [`VrmUsageViolations`] listing the violations.
+    Flag,
+}
+
+/// A usage needed by [`VrmUsagePolicy`] but not permitted by the license of the model.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub enum VrmUsageViolation {
+    /// The users of the application are not allowed to use the model as an avatar.
+    AvatarPermission,
+    /// The commercial usage of the application exceeds the one permitted by the license.
+    CommercialUsage,
+    /// The modification of the application exceeds the one permitted by the license.
+    Modification,
+    /// The application uses the avatar in excessively violent contexts, which the license forbids.
+    ExcessivelyViolentUsage,
+    /// The application uses the avatar in excessively sexual contexts, which the license forbids.
+    ExcessivelySexualUsage,
+    /// The application uses the avatar for political or religious purposes, which the license forbids.
+    PoliticalOrReligiousUsage,
+    /// The application uses the avatar for antisocial or hate purposes, which the license forbids.
+    AntisocialOrHateUsage,
+    /// The application redistributes the model, which the license forbids.
+    Redistribution,
+}
+
+/// The violations of [`VrmUsagePolicy`].
+///
+/// This component is inserted into the entity of VRM
+/// when the policy action is [`VrmUsagePolicyAction::Flag`] and the model violates the policy.
+#[derive(Component, Debug, Clone, PartialEq, Reflect, Deref)]
+#[reflect(Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub struct VrmUsageViolations(pub Vec<VrmUsageViolation>);
+
+impl VrmUsagePolicy {
+    /// Returns the usages needed by this policy that the license of the model does not permit.
+    pub fn violations(
+        &self,
+        meta: &VrmMeta,
+    ) -> Vec<VrmUsageViolation> {
+        [
+            (
+                meta.avatar_permission < self.avatar_permission,
+                VrmUsageViolation::AvatarPermission,
+            ),
+            (
+                meta.commercial_usage < self.commercial_usage,
+                VrmUsageViolation::CommercialUsage,
+            ),
+            (
+                meta.modification < self.modification,
+                VrmUsageViolation::Modification,
+            ),
+            (
+                self.excessively_violent_usage && !meta.allow_excessively_violent_usage,
+                VrmUsageViolation::ExcessivelyViolentUsage,
+            ),
+            (
+                self.excessively_sexual_usage && !meta.allow_excessively_sexual_usage,
+                VrmUsageViolation::ExcessivelySexualUsage,
+            ),
+            (
+                self.political_or_religious_usage && !meta.allow_political_or_religious_usage,
+                VrmUsageViolation::PoliticalOrReligiousUsage,
+            ),
+            (
+                self.antisocial_or_hate_usage && !meta.allow_antisocial_or_hate_usage,
+                VrmUsageViolation::AntisocialOrHateUsage,
+            ),
+            (
+                self.redistribution && !meta.allow_redistribution,
+                VrmUsageViolation::Redistribution,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(violated, violation)| violated.then_some(violation))
+        .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::vrm::meta::usage_policy::{VrmUsagePolicy, VrmUsageViolation};
+    use crate::vrm::meta::vrm_meta::{CommercialUsage, VrmMeta};
+
+    #[test]
+    fn detect_violations() {
+        let meta = VrmMeta {
+            commercial_usage: CommercialUsage::PersonalProfit,
+            allow_excessively_violent_usage: true,
+            ..Default::default()
+        };
+        let policy = VrmUsagePolicy {
+            commercial_usage: CommercialUsage::Corporation,
+            excessively_violent_usage: true,
+            redistribution: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            policy.violations(&meta),
+            vec![
+                VrmUsageViolation::CommercialUsage,
+                VrmUsageViolation::Redistribution
+            ]
+        );
+        assert!(VrmUsagePolicy::default().violations(&meta).is_empty());
+    }
+}
@@ -0,0 +1,153 @@
+use crate::vrm::gltf::extensions::vrmc_vrm::Meta;
+use bevy::prelude::*;
+
+/// The meta information of the VRM model, such as the name, authors and license.
+///
+/// This component is inserted into the entity of VRM when it is spawned.
+/// VRM 0.x meta is converted into the VRM 1.0 structure.
+///
+/// The permission fields are compared against [`VrmUsagePolicy`](crate::prelude::VrmUsagePolicy)
+/// before the model is spawned.
+#[derive(Component, Debug, Clone, PartialEq, Reflect, Default)]
+#[reflect(Component, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub struct VrmMeta {
+    pub name: Option<String>,
+    pub version: Option<String>,
+    pub authors: Vec<String>,
+    /// The index of the glTF image used as the thumbnail.
+    pub thumbnail_image: Option<usize>,
+    pub license_url: Option<String>,
+    pub other_license_url: Option<String>,
+    pub avatar_permission: AvatarPermission,
+    pub allow_excessively_violent_usage: bool,
+    pub allow_excessively_sexual_usage: bool,
+    pub commercial_usage: CommercialUsage,
+    pub allow_political_or_religious_usage: bool,
+    pub allow_antisocial_or_hate_usage: bool,
+    pub credit_notation: CreditNotation,
+    pub allow_redistribution: bool,
+    pub modification: Modification,
+}
+
+impl From<&Meta> for VrmMeta {
+    fn from(meta: &Meta) -> Self {
+        Self {
+            name: meta.name.clone(),
+            version: meta.version.clone(),
+            authors: meta.authors.clone(),
+            thumbnail_image: meta
+                .thumbnail_image
+                .and_then(|index| usize::try_from(index).ok()),
+            license_url: meta.license_url.clone(),
+            other_license_url: meta.other_license_url.clone(),
+            avatar_permission: AvatarPermission::from_spec(meta.avatar_permission.as_deref()),
+            allow_excessively_violent_usage: meta.allow_excessively_violent_usage,
+            allow_excessively_sexual_usage: meta.allow_excessively_sexual_usage,
+            commercial_usage: CommercialUsage::from_spec(meta.commercial_usage.as_deref()),
+            allow_political_or_religious_usage: meta.allow_political_or_religious_usage,
+            allow_antisocial_or_hate_usage: meta.allow_antisocial_or_hate_usage,
+            credit_notation: CreditNotation::from_spec(meta.credit_notation.as_deref()),
+            allow_redistribution: meta.allow_redistribution,
+            modification: Modification::from_spec(meta.modification.as_deref()),
+        }
+    }
+}
+
+/// Who is allowed to use the model as an avatar.
+///
+/// The variants are ordered from the most restrictive to the most permissive.
+/// Unknown or missing values are treated as [`AvatarPermission::OnlyAuthor`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum AvatarPermission {
+    #[default]
+    OnlyAuthor,
+    OnlySeparatelyLicensedPerson,
+    Everyone,
+}
+
+impl AvatarPermission {
+    fn from_spec(value: Option<&str>) -> Self {
+        match value {
+            Some("onlySeparatelyLicensedPerson") => Self::OnlySeparatelyLicensedPerson,
+            Some("everyone") => Self::Everyone,
+            _ => Self::OnlyAuthor,
+        }
+    }
+}
+
+/// The commercial usage permitted by the license.
+///
+/// The variants are ordered from the most restrictive to the most permissive.
+/// Unknown or missing values are treated as [`CommercialUsage::PersonalNonProfit`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum CommercialUsage {
+    #[default]
+    PersonalNonProfit,
+    PersonalProfit,
+    Corporation,
+}
+
+impl CommercialUsage {
+    fn from_spec(value: Option<&str>) -> Self {
+        match value {
+            Some("personalProfit") => Self::PersonalProfit,
+            Some("corporation") => Self::Corporation,
+            _ => Self::PersonalNonProfit,
+        }
+    }
+}
+
+/// Whether the credit of the author is required.
+///
+/// Unknown or missing values are treated as [`CreditNotation::Required`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum CreditNotation {
+    #[default]
+    Required,
+    Unnecessary,
+}
+
+impl CreditNotation {
+    fn from_spec(value: Option<&str>) -> Self {
+        match value {
+            Some("unnecessary") => Self::Unnecessary,
+            _ => Self::Required,
+        }
+    }
+}
+
+/// The modification permitted by the license.
+///
+/// The variants are ordered from the most restrictive to the most permissive.
+/// Unknown or missing values are treated as [`Modification::Prohibited`].
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash, Default, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub enum Modification {
+    #[default]
+    Prohibited,
+    AllowModification,
+    AllowModificationRedistribution,
+}
+
+impl Modification {
+    fn from_spec(value: Option<&str>) -> Self {
+        match value {
+            Some("allowModification") => Self::AllowModification,
+            Some("allowModificationRedistribution") => Self::AllowModificationRedistribution,
+            _ => Self::Prohibited,
+        }
+    }
+}
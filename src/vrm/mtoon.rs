@@ -2,13 +2,15 @@ mod material;
 mod outline_pass;
 mod setup;
 
-use crate::error::vrm_error;
+use crate::error::VrmError;
 use crate::prelude::*;
+use crate::vrm::gltf::extensions::vrm0::Vrm0;
 use crate::vrm::gltf::materials::VrmcMaterialsExtensitions;
 use crate::vrm::mtoon::outline_pass::MToonOutlinePlugin;
 use crate::vrm::mtoon::setup::MToonMaterialSetupPlugin;
 use bevy::asset::{load_internal_asset, weak_handle, AssetId};
 use bevy::prelude::*;
+use serde::Deserialize;
 use std::collections::HashMap;
 
 pub mod prelude {
@@ -32,6 +34,7 @@ impl Plugin for MtoonMaterialPlugin {
         app.register_type::<MToonMaterial>()
             .register_type::<MToonOutline>()
             .register_type::<VrmcMaterialRegistry>()
+            .register_type::<MToonMaterialSource>()
             .register_type::<RimLighting>()
             .register_type::<UVAnimation>()
             .register_type::<Shade>()
@@ -58,7 +61,12 @@ impl Plugin for MtoonMaterialPlugin {
     }
 }
 
-#[derive(Component, Default, Debug, Reflect)]
+/// The glTF material that [`MToonMaterial`] of the mesh entity was converted from.
+#[derive(Component, Debug, Copy, Clone, Reflect)]
+#[reflect(Component)]
+pub(crate) struct MToonMaterialSource(pub(crate) AssetId<StandardMaterial>);
+
+#[derive(Component, Default, Debug, Clone, Reflect)]
 #[reflect(Component)]
 pub struct VrmcMaterialRegistry {
     pub images: Vec<Handle<Image>>,
@@ -66,33 +74,44 @@ pub struct VrmcMaterialRegistry {
 }
 
 impl VrmcMaterialRegistry {
+    /// Creates the registry from the `VRMC_materials_mtoon` extensions of the glTF materials.
     pub fn new(
         gltf: &Gltf,
         images: Vec<Handle<Image>>,
-    ) -> Self {
-        Self::try_new(gltf, images).unwrap_or_default()
+    ) -> Result<Self, VrmError> {
+        Self::from_gltf(gltf, None, images)
     }
 
-    fn try_new(
+    /// Creates the registry, converting the `MToon` properties of VRM 0.x if `vrm0` is given.
+    pub(crate) fn from_gltf(
         gltf: &Gltf,
+        vrm0: Option<&Vrm0>,
         images: Vec<Handle<Image>>,
-    ) -> Option<Self> {
-        let materials = gltf
-            .source
-            .as_ref()?
+    ) -> Result<Self, VrmError> {
+        let Some(source) = gltf.source.as_ref() else {
+            return Ok(Self {
+                images,
+                ..default()
+            });
+        };
+        let materials = source
             .materials()
-            .flat_map(|m| {
+            .filter_map(|m| {
                 let asset_id = gltf.named_materials.get(m.name()?)?.id();
-                let extensions = m.extensions()?;
-                match serde_json::from_value(extensions.get("VRMC_materials_mtoon")?.clone()) {
-                    Ok(properties) => Some((asset_id, properties)),
-                    Err(e) => {
-                        vrm_error!("Failed to parse VRMC_materials_mtoon", e);
-                        None
-                    }
+                if let Some(vrm0) = vrm0 {
+                    return Some(Ok((asset_id, vrm0.to_vrmc_materials_mtoon(m.index()?)?)));
                 }
+                let extension = m.extensions()?.get("VRMC_materials_mtoon")?;
+                Some(
+                    VrmcMaterialsExtensitions::deserialize(extension)
+                        .map(|properties| (asset_id, properties))
+                        .map_err(|source| VrmError::InvalidMaterial {
+                            material: m.name().unwrap_or_default().to_string(),
+                            source,
+                        }),
+                )
             })
-            .collect();
-        Some(Self { materials, images })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { materials, images })
     }
 }
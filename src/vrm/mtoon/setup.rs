@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use crate::vrm::mtoon::MToonMaterialSource;
 use bevy::app::{App, Plugin};
 use bevy::asset::Assets;
 use bevy::prelude::*;
@@ -38,6 +39,7 @@ fn turn_to_mtoon_material(
         };
         let mut cmd = commands.entity(entity);
         cmd.remove::<MeshMaterial3d<StandardMaterial>>()
+            .insert(MToonMaterialSource(handle.id()))
             .insert(MeshMaterial3d(
                 mtoon_materials.add(MToonMaterial {
                     base_color_texture: base.base_color_texture.clone(),
@@ -13,8 +13,8 @@ use bevy::prelude::*;
 /// The component that holds the spring bone state of each Joint
 ///
 /// Implement the method described in the  [Official documentation](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_springBone-1.0/README.ja.md#%E5%88%9D%E6%9C%9F%E5%8C%96)
-#[derive(PartialEq, Debug, Clone, Default, Reflect, Component)]
-#[reflect(Default, Component)]
+#[derive(PartialEq, Debug, Clone, Default, Reflect)]
+#[reflect(Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub(crate) struct SpringJointState {
@@ -26,13 +26,24 @@ pub(crate) struct SpringJointState {
     initial_local_rotation: Quat,
 }
 
+/// The states of the joints of the chain, paired with the entities of the joints.
+///
+/// This component is inserted next to [`SpringRoot`].
+/// The states are kept per chain since the chains branching from the same joint share it.
+#[derive(Component, PartialEq, Debug, Clone, Default, Deref, DerefMut, Reflect)]
+#[reflect(Default, Component)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub(crate) struct SpringJointStates(pub Vec<(Entity, SpringJointState)>);
+
 #[derive(Component, Debug, Clone, PartialEq, Default, Reflect)]
 #[reflect(Component, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
 pub(crate) struct SpringRoot {
     /// Represents a list of entity of spring joints belonging to the spring chain.
-    /// This component is inserted into the root entity of the chain.
+    /// This component is inserted into the root entity of the chain,
+    /// or the next joint if another chain branching from the same joint already has it.
     pub joints: SpringJoints,
 
     pub colliders: SpringColliders,
@@ -81,6 +92,7 @@ impl Plugin for VrmSpringBonePlugin {
     ) {
         app.register_type::<SpringRoot>()
             .register_type::<SpringJointState>()
+            .register_type::<SpringJointStates>()
             .register_type::<SpringJoints>()
             .register_type::<SpringColliders>()
             .register_type::<SpringCenterNode>()
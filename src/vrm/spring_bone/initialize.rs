@@ -4,9 +4,11 @@ use crate::vrm::spring_bone::registry::{
     SpringColliderRegistry, SpringJointPropsRegistry, SpringNodeRegistry,
 };
 use crate::vrm::spring_bone::{
-    SpringCenterNode, SpringColliders, SpringJointState, SpringJoints, SpringRoot,
+    SpringCenterNode, SpringColliders, SpringJointState, SpringJointStates, SpringJoints,
+    SpringRoot,
 };
 use bevy::app::{App, Update};
+use bevy::platform::collections::HashSet;
 use bevy::prelude::*;
 
 #[derive(Event)]
@@ -72,6 +74,7 @@ fn apply_initialize_spring_roots(
     let Ok(registry) = models.get(entity) else {
         return;
     };
+    let mut roots = HashSet::new();
     for spring_root in registry.0.iter().map(|spring| SpringRoot {
         center_node: SpringCenterNode(
             spring
@@ -97,51 +100,118 @@ fn apply_initialize_spring_roots(
                 .collect(),
         ),
     }) {
-        let Some(root) = spring_root.joints.first() else {
+        let Some(root) = spring_root
+            .joints
+            .iter()
+            .find(|joint| roots.insert(**joint))
+            .copied()
+        else {
             continue;
         };
-        commands.entity(*root).insert(spring_root);
+        commands.entity(root).insert(spring_root);
     }
 }
 
 fn init_spring_joint_states(
     par_commands: ParallelCommands,
-    spring_roots: Query<&SpringRoot, Added<SpringRoot>>,
+    spring_roots: Query<(Entity, &SpringRoot), Added<SpringRoot>>,
     joints: Query<&Transform>,
     global_transforms: Query<&GlobalTransform>,
 ) {
-    spring_roots.par_iter().for_each(|root| {
-        for w in root.joints.windows(2) {
-            let head_entity = w[0];
-            let joint_entity = w[1];
-            let Ok(head_tf) = joints.get(head_entity) else {
-                continue;
-            };
-            let Ok(tail_tf) = joints.get(joint_entity) else {
-                continue;
-            };
-            let Ok(tail_gtf) = global_transforms.get(joint_entity) else {
-                continue;
-            };
-            let tail_pos = root
-                .center_node
-                .and_then(|center| global_transforms.get(center).ok())
-                .map(|center_gtf| tail_gtf.reparented_to(center_gtf).translation)
-                .unwrap_or(tail_gtf.translation());
-            let state = SpringJointState {
-                prev_tail: tail_pos,
-                current_tail: tail_pos,
-                bone_axis: tail_tf.translation.normalize(),
-                bone_length: tail_tf.translation.length(),
-                initial_local_matrix: head_tf.compute_matrix(),
-                initial_local_rotation: head_tf.rotation,
-            };
-            par_commands.command_scope(|mut commands| {
-                commands.entity(head_entity).insert(state);
-            });
-        }
+    spring_roots.par_iter().for_each(|(entity, root)| {
+        let center_gtf = root
+            .center_node
+            .and_then(|center| global_transforms.get(center).ok());
+        let states = root
+            .joints
+            .windows(2)
+            .filter_map(|w| {
+                let head_entity = w[0];
+                let joint_entity = w[1];
+                let head_tf = joints.get(head_entity).ok()?;
+                let tail_tf = joints.get(joint_entity).ok()?;
+                let tail_gtf = global_transforms.get(joint_entity).ok()?;
+                let tail_pos = center_gtf
+                    .map(|center_gtf| tail_gtf.reparented_to(center_gtf).translation)
+                    .unwrap_or(tail_gtf.translation());
+                let state = SpringJointState {
+                    prev_tail: tail_pos,
+                    current_tail: tail_pos,
+                    bone_axis: tail_tf.translation.normalize(),
+                    bone_length: tail_tf.translation.length(),
+                    initial_local_matrix: head_tf.compute_matrix(),
+                    initial_local_rotation: head_tf.rotation,
+                };
+                Some((head_entity, state))
+            })
+            .collect();
+        par_commands.command_scope(|mut commands| {
+            commands.entity(entity).insert(SpringJointStates(states));
+        });
     });
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use crate::success;
+    use crate::tests::{test_app, TestResult};
+    use crate::vrm::spring_bone::initialize::{
+        apply_initialize_spring_roots, init_spring_joint_states, RequestInitializeSpringBone,
+    };
+    use crate::vrm::spring_bone::registry::{SpringNode, SpringNodeRegistry};
+    use crate::vrm::spring_bone::{SpringJointStates, SpringRoot};
+    use bevy::prelude::*;
+
+    #[test]
+    fn share_the_joint_branching_chains() -> TestResult {
+        let mut app = test_app();
+        app.add_systems(Update, init_spring_joint_states)
+            .add_observer(apply_initialize_spring_roots);
+        // 0 ─┬─ 1 ── 2
+        //    └─ 3 ── 4
+        let joints = (0..5)
+            .map(|i| {
+                app.world_mut()
+                    .spawn((
+                        Name::new(format!("joint{i}")),
+                        Transform::from_xyz(0.0, -0.1, 0.0),
+                        GlobalTransform::default(),
+                    ))
+                    .id()
+            })
+            .collect::<Vec<_>>();
+        for (parent, child) in [(0, 1), (1, 2), (0, 3), (3, 4)] {
+            app.world_mut()
+                .entity_mut(joints[child])
+                .insert(ChildOf(joints[parent]));
+        }
+        let spring = |indices: [usize; 3]| SpringNode {
+            joints: indices.map(|i| Name::new(format!("joint{i}"))).to_vec(),
+            ..default()
+        };
+        let vrm = app
+            .world_mut()
+            .spawn(SpringNodeRegistry(vec![
+                spring([0, 3, 4]),
+                spring([0, 1, 2]),
+            ]))
+            .add_child(joints[0])
+            .id();
+        app.world_mut()
+            .trigger_targets(RequestInitializeSpringBone, vrm);
+        app.update();
+
+        let mut roots = app.world_mut().query::<(&SpringRoot, &SpringJointStates)>();
+        let chains = roots
+            .iter(app.world())
+            .map(|(root, states)| {
+                assert_eq!(states.len(), 2);
+                root.joints.0.clone()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(chains.len(), 2);
+        assert!(chains.contains(&vec![joints[0], joints[3], joints[4]]));
+        assert!(chains.contains(&vec![joints[0], joints[1], joints[2]]));
+        success!()
+    }
+}
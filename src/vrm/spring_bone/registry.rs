@@ -3,8 +3,6 @@ use crate::vrm::gltf::extensions::vrmc_spring_bone::{
 };
 use crate::vrm::spring_bone::SpringJointProps;
 use bevy::app::App;
-use bevy::asset::{Assets, Handle};
-use bevy::gltf::GltfNode;
 use bevy::math::Vec3;
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
@@ -31,16 +29,13 @@ pub(crate) struct SpringColliderRegistry(pub(crate) HashMap<Name, ColliderShape>
 impl SpringColliderRegistry {
     pub fn new(
         colliders: &[Collider],
-        node_assets: &Assets<GltfNode>,
-        nodes: &[Handle<GltfNode>],
+        node_names: &[Name],
     ) -> Self {
         Self(
             colliders
                 .iter()
                 .filter_map(|collider| {
-                    let node_handle = nodes.get(collider.node)?;
-                    let node = node_assets.get(node_handle)?;
-                    Some((Name::new(node.name.clone()), collider.shape))
+                    Some((node_names.get(collider.node)?.clone(), collider.shape))
                 })
                 .collect(),
         )
@@ -56,18 +51,15 @@ pub(crate) struct SpringJointPropsRegistry(pub(crate) HashMap<Name, SpringJointP
 impl SpringJointPropsRegistry {
     pub fn new(
         joints: &[SpringJoint],
-        node_assets: &Assets<GltfNode>,
-        nodes: &[Handle<GltfNode>],
+        node_names: &[Name],
     ) -> Self {
         Self(
             joints
                 .iter()
                 .filter_map(|joint| {
-                    let node_handle = nodes.get(joint.node)?;
-                    let node = node_assets.get(node_handle)?;
                     let dir = joint.gravity_dir?;
                     Some((
-                        Name::new(node.name.clone()),
+                        node_names.get(joint.node)?.clone(),
                         SpringJointProps {
                             drag_force: joint.drag_force?,
                             gravity_power: joint.gravity_power?,
@@ -92,7 +84,7 @@ pub(crate) struct SpringNode {
     pub colliders: Vec<(Name, ColliderShape)>,
 }
 
-#[derive(Component, Deref, Default, Reflect)]
+#[derive(Component, Deref, Debug, Default, Clone, Reflect)]
 #[reflect(Component, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
@@ -101,8 +93,7 @@ pub(crate) struct SpringNodeRegistry(pub Vec<SpringNode>);
 impl SpringNodeRegistry {
     pub fn new(
         spring_bone: &VRMCSpringBone,
-        node_assets: &Assets<GltfNode>,
-        nodes: &[Handle<GltfNode>],
+        node_names: &[Name],
     ) -> Self {
         Self(
             spring_bone
@@ -112,12 +103,12 @@ impl SpringNodeRegistry {
                     joints: spring
                         .joints
                         .iter()
-                        .filter_map(|joint| get_node_name(joint.node, node_assets, nodes))
+                        .filter_map(|joint| node_names.get(joint.node).cloned())
                         .collect(),
-                    colliders: obtain_colliders(spring_bone, spring, node_assets, nodes),
+                    colliders: obtain_colliders(spring_bone, spring, node_names),
                     center: spring
                         .center
-                        .and_then(|index| get_node_name(index, node_assets, nodes)),
+                        .and_then(|index| node_names.get(index).cloned()),
                 })
                 .collect(),
         )
@@ -127,8 +118,7 @@ impl SpringNodeRegistry {
 fn obtain_colliders(
     spring_bone: &VRMCSpringBone,
     spring: &Spring,
-    node_assets: &Assets<GltfNode>,
-    nodes: &[Handle<GltfNode>],
+    node_names: &[Name],
 ) -> Vec<(Name, ColliderShape)> {
     let Some(collider_groups) = spring.collider_groups.as_ref() else {
         return vec![];
@@ -137,18 +127,8 @@ fn obtain_colliders(
         .spring_colliders(collider_groups)
         .iter()
         .flat_map(|collider| {
-            let name = get_node_name(collider.node, node_assets, nodes)?;
-            Some((name, collider.shape))
+            let name = node_names.get(collider.node)?;
+            Some((name.clone(), collider.shape))
         })
         .collect()
 }
-
-fn get_node_name(
-    node_index: usize,
-    node_assets: &Assets<GltfNode>,
-    nodes: &[Handle<GltfNode>],
-) -> Option<Name> {
-    let node_handle = nodes.get(node_index)?;
-    let node = node_assets.get(node_handle)?;
-    Some(Name::new(node.name.clone()))
-}
@@ -1,6 +1,6 @@
 use crate::system_set::VrmSystemSets;
 use crate::vrm::gltf::extensions::vrmc_spring_bone::ColliderShape;
-use crate::vrm::spring_bone::{SpringJointProps, SpringJointState, SpringRoot};
+use crate::vrm::spring_bone::{SpringJointProps, SpringJointStates, SpringRoot};
 use bevy::app::{Animation, App};
 use bevy::math::Vec3;
 use bevy::prelude::TransformSystem::TransformPropagate;
@@ -26,19 +26,20 @@ impl Plugin for SpringBoneUpdatePlugin {
 
 fn update_spring_bones(
     mut transforms: Query<(&mut Transform, &mut GlobalTransform)>,
-    mut joints: Query<(&ChildOf, &mut SpringJointState, &SpringJointProps)>,
-    spring_roots: Query<&SpringRoot>,
+    joints: Query<(&ChildOf, &SpringJointProps)>,
+    mut spring_roots: Query<(&SpringRoot, &mut SpringJointStates)>,
     time: Res<Time>,
 ) {
     let delta_time = time.delta_secs();
-    for spring_root in spring_roots.iter() {
+    for (spring_root, mut states) in spring_roots.iter_mut() {
         let center_gtf = spring_root
             .center_node
             .and_then(|center| transforms.get(center).ok())
             .map(|(_, gtf)| gtf)
             .copied();
-        for joint in spring_root.joints.iter().copied() {
-            let Ok((child_of, mut state, props)) = joints.get_mut(joint) else {
+        for (joint, state) in states.iter_mut() {
+            let joint = *joint;
+            let Ok((child_of, props)) = joints.get(joint) else {
                 continue;
             };
             let parent_gtf = transforms
@@ -0,0 +1,601 @@
+//! This module provides [`VrmValidator`], which checks the VRM extensions against the specification.
+//!
+//! The loader runs it on every VRM and keeps the report in [`VrmAsset::validation`](crate::prelude::VrmAsset::validation).
+//! Only the models with [fatal](VrmValidationIssue::is_fatal) errors are refused; the other findings do not prevent loading.
+
+use crate::error::VrmError;
+use crate::vrm::gltf::extensions::vrm0::GltfDocument;
+use crate::vrm::gltf::extensions::vrmc_spring_bone::VRMCSpringBone;
+use crate::vrm::gltf::extensions::vrmc_vrm::Humanoid;
+use crate::vrm::gltf::extensions::{check_spec_version, VrmExtensions, VRMC_SPRING_BONE, VRMC_VRM};
+use crate::vrm::gltf::glb::split_glb;
+use bevy::gltf::Gltf;
+use serde::Deserialize;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// Validates the VRM extensions of a model.
+///
+/// ```no_run
+/// use bevy_vrm1::prelude::*;
+///
+/// let bytes = std::fs::read("<vrm>.vrm").unwrap();
+/// let report = VrmValidator::validate_bytes(&bytes).unwrap();
+/// for error in &report.errors {
+///     println!("error: {error}");
+/// }
+/// for warning in &report.warnings {
+///     println!("warning: {warning}");
+/// }
+/// ```
+pub struct VrmValidator;
+
+impl VrmValidator {
+    /// Validates the extensions parsed from the glTF asset.
+    pub fn validate(
+        extensions: &VrmExtensions,
+        gltf: &Gltf,
+    ) -> VrmValidationReport {
+        Self::validate_document(extensions, &GltfDocument::from(gltf))
+    }
+
+    /// Validates the `.vrm` file, either GLB or glTF JSON.
+    ///
+    /// Returns an error only if the file or the extensions cannot be parsed;
+    /// violations of the specification are reported in [`VrmValidationReport`].
+    pub fn validate_bytes(bytes: &[u8]) -> Result<VrmValidationReport, VrmError> {
+        let (json, _) = split_glb(bytes)?;
+        let root: serde_json::Value =
+            serde_json::from_slice(json).map_err(|e| VrmError::InvalidGltf(e.to_string()))?;
+        let document =
+            GltfDocument::deserialize(&root).map_err(|e| VrmError::InvalidGltf(e.to_string()))?;
+        let json = root
+            .get("extensions")
+            .and_then(serde_json::Value::as_object)
+            .ok_or(VrmError::MissingExtension(VRMC_VRM))?;
+        let extensions = VrmExtensions::parse_document(json, &document)?;
+        Ok(Self::validate_document(&extensions, &document))
+    }
+
+    pub(crate) fn validate_document(
+        extensions: &VrmExtensions,
+        document: &GltfDocument,
+    ) -> VrmValidationReport {
+        let mut report = VrmValidationReport::default();
+        let validation = Validation {
+            extensions,
+            document,
+            parents: node_parents(document),
+        };
+        validation.check_spec_versions(&mut report);
+        validation.check_meta(&mut report);
+        validation.check_humanoid(&mut report);
+        validation.check_expressions(&mut report);
+        validation.check_first_person(&mut report);
+        if let Some(spring_bone) = extensions.vrmc_spring_bone.as_ref() {
+            validation.check_spring_bone(spring_bone, &mut report);
+        }
+        report
+    }
+}
+
+/// The result of [`VrmValidator`].
+///
+/// Errors are violations of the specification, and warnings are properties the model may not behave as the author intended with.
+/// The loader refuses only the models with [fatal](VrmValidationIssue::is_fatal) errors,
+/// so the others are loaded with the report, e.g. to show it to the uploader.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct VrmValidationReport {
+    pub errors: Vec<VrmValidationIssue>,
+    pub warnings: Vec<VrmValidationIssue>,
+}
+
+impl VrmValidationReport {
+    /// Returns `true` if no error is found.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Returns `true` if the model can be loaded, which means no error is [fatal](VrmValidationIssue::is_fatal).
+    #[inline]
+    pub fn is_loadable(&self) -> bool {
+        !self.errors.iter().any(VrmValidationIssue::is_fatal)
+    }
+}
+
+impl Display for VrmValidationReport {
+    fn fmt(
+        &self,
+        f: &mut Formatter<'_>,
+    ) -> std::fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if 0 < i {
+                write!(f, "; ")?;
+            }
+            write!(f, "{error}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A violation of the VRM specification found by [`VrmValidator`].
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum VrmValidationIssue {
+    /// The major version of the extension is not supported.
+    #[error("Unsupported spec version of {extension}: {version}")]
+    UnsupportedSpecVersion {
+        extension: &'static str,
+        version: String,
+    },
+
+    /// The minor version of the extension is newer than the supported one,
+    /// so some properties may be ignored.
+    #[error("{extension} {version} is newer than 1.0; unknown properties are ignored")]
+    NewerSpecVersion {
+        extension: &'static str,
+        version: String,
+    },
+
+    /// The meta information, which contains the license, is missing.
+    #[error("Not found the meta information")]
+    MissingMeta,
+
+    /// The humanoid bone required by the specification is missing.
+    #[error("Not found the required humanoid bone: {0}")]
+    MissingHumanoidBone(String),
+
+    /// The humanoid bone is not defined in the specification, so it is ignored.
+    #[error("Unknown humanoid bone: {0}")]
+    UnknownHumanoidBone(String),
+
+    /// The node of the humanoid bone is not a descendant of the node of its parent bone.
+    #[error("The humanoid bone {bone} is not a descendant of {parent}")]
+    InvalidBoneHierarchy { bone: String, parent: String },
+
+    /// The node index is out of range.
+    #[error("The node index {index} of {referrer} is out of range")]
+    InvalidNodeIndex { referrer: String, index: usize },
+
+    /// The index of `VRMC_springBone.colliders` is out of range.
+    #[error("The collider index {index} of {referrer} is out of range")]
+    InvalidColliderIndex { referrer: String, index: usize },
+
+    /// The index of `VRMC_springBone.colliderGroups` is out of range.
+    #[error("The collider group index {index} of {referrer} is out of range")]
+    InvalidColliderGroupIndex { referrer: String, index: usize },
+
+    /// The spring has too few joints to swing.
+    #[error("The spring {0} needs at least 2 joints")]
+    TooFewSpringJoints(String),
+
+    /// The node bound to the expression has no mesh.
+    #[error("The node {node} bound to the expression {expression} has no mesh")]
+    MissingMesh { expression: String, node: usize },
+
+    /// The morph target index bound to the expression exceeds the morph targets of the mesh.
+    #[error("The morph target index {index} bound to the expression {expression} is out of range; the mesh has {count} morph targets")]
+    InvalidMorphTargetIndex {
+        expression: String,
+        index: usize,
+        count: usize,
+    },
+
+    /// The weight bound to the expression is not in `0.0..=1.0`.
+    #[error("The weight {weight} bound to the expression {expression} is not in the range 0..=1")]
+    MorphTargetWeightOutOfRange { expression: String, weight: f32 },
+}
+
+impl VrmValidationIssue {
+    /// Returns `true` if the issue refers to an index out of range of the glTF or the extensions.
+    pub fn is_out_of_range(&self) -> bool {
+        matches!(
+            self,
+            Self::InvalidNodeIndex { .. }
+                | Self::InvalidColliderIndex { .. }
+                | Self::InvalidColliderGroupIndex { .. }
+                | Self::InvalidMorphTargetIndex { .. }
+        )
+    }
+
+    /// Returns `true` if the loader refuses the model with the issue.
+    ///
+    /// Besides the out-of-range indices, a missing required humanoid bone or an unsupported spec version
+    /// leaves the model without a skeleton that can be initialized.
+    pub fn is_fatal(&self) -> bool {
+        self.is_out_of_range()
+            || matches!(
+                self,
+                Self::MissingHumanoidBone(_) | Self::UnsupportedSpecVersion { .. }
+            )
+    }
+}
+
+struct Validation<'a> {
+    extensions: &'a VrmExtensions,
+    document: &'a GltfDocument,
+    parents: Vec<Option<usize>>,
+}
+
+impl Validation<'_> {
+    fn check_spec_versions(
+        &self,
+        report: &mut VrmValidationReport,
+    ) {
+        // VRM 0.x is converted into VRM 1.0, so the converted versions are not checked.
+        if self.extensions.is_vrm0() {
+            return;
+        }
+        let versions = std::iter::once((VRMC_VRM, &self.extensions.vrmc_vrm.spec_version)).chain(
+            self.extensions
+                .vrmc_spring_bone
+                .as_ref()
+                .map(|spring_bone| (VRMC_SPRING_BONE, &spring_bone.spec_version)),
+        );
+        for (extension, version) in versions {
+            let version = version.clone();
+            if check_spec_version(extension, &version).is_err() {
+                report
+                    .errors
+                    .push(VrmValidationIssue::UnsupportedSpecVersion { extension, version });
+            } else if !version.starts_with("1.0") {
+                report
+                    .warnings
+                    .push(VrmValidationIssue::NewerSpecVersion { extension, version });
+            }
+        }
+    }
+
+    fn check_meta(
+        &self,
+        report: &mut VrmValidationReport,
+    ) {
+        if self.extensions.vrmc_vrm.meta.is_none() {
+            report.warnings.push(VrmValidationIssue::MissingMeta);
+        }
+    }
+
+    fn check_humanoid(
+        &self,
+        report: &mut VrmValidationReport,
+    ) {
+        let human_bones = &self.extensions.vrmc_vrm.humanoid.human_bones;
+        for bone in Humanoid::REQUIRED_BONES {
+            if !human_bones.contains_key(bone) {
+                report
+                    .errors
+                    .push(VrmValidationIssue::MissingHumanoidBone(bone.to_string()));
+            }
+        }
+
+        let mut bones = human_bones.iter().collect::<Vec<_>>();
+        bones.sort_by_key(|(bone, _)| bone.as_str());
+        for (bone, target) in bones {
+            if !self.check_node(format!("humanoid bone {bone}"), target.node, report) {
+                continue;
+            }
+            if bone != "hips" && humanoid_parent(bone).is_none() {
+                report
+                    .warnings
+                    .push(VrmValidationIssue::UnknownHumanoidBone(bone.clone()));
+                continue;
+            }
+            let mut parent = humanoid_parent(bone);
+            while let Some(name) = parent.as_deref() {
+                if human_bones.contains_key(name) {
+                    break;
+                }
+                parent = humanoid_parent(name);
+            }
+            let Some(parent) = parent else {
+                continue;
+            };
+            let parent_node = human_bones[parent.as_str()].node;
+            if parent_node < self.document.nodes.len()
+                && !self.is_descendant(target.node, parent_node)
+            {
+                report
+                    .errors
+                    .push(VrmValidationIssue::InvalidBoneHierarchy {
+                        bone: bone.clone(),
+                        parent,
+                    });
+            }
+        }
+    }
+
+    fn check_expressions(
+        &self,
+        report: &mut VrmValidationReport,
+    ) {
+        let Some(expressions) = self.extensions.vrmc_vrm.expressions.as_ref() else {
+            return;
+        };
+        let mut presets = expressions.iter().collect::<Vec<_>>();
+        presets.sort_by_key(|(name, _)| name.as_str());
+        for (expression, preset) in presets {
+            for bind in preset.morph_target_binds.iter().flatten() {
+                if !self.check_node(format!("expression {expression}"), bind.node, report) {
+                    continue;
+                }
+                let Some(count) = self.document.morph_target_count(bind.node) else {
+                    report.errors.push(VrmValidationIssue::MissingMesh {
+                        expression: expression.clone(),
+                        node: bind.node,
+                    });
+                    continue;
+                };
+                if count <= bind.index {
+                    report
+                        .errors
+                        .push(VrmValidationIssue::InvalidMorphTargetIndex {
+                            expression: expression.clone(),
+                            index: bind.index,
+                            count,
+                        });
+                }
+                if !(0.0..=1.0).contains(&bind.weight) {
+                    report
+                        .warnings
+                        .push(VrmValidationIssue::MorphTargetWeightOutOfRange {
+                            expression: expression.clone(),
+                            weight: bind.weight,
+                        });
+                }
+            }
+        }
+    }
+
+    fn check_first_person(
+        &self,
+        report: &mut VrmValidationReport,
+    ) {
+        let Some(first_person) = self.extensions.vrmc_vrm.first_person.as_ref() else {
+            return;
+        };
+        for annotation in &first_person.mesh_annotations {
+            match usize::try_from(annotation.node) {
+                Ok(node) => {
+                    self.check_node("firstPerson mesh annotation".to_string(), node, report);
+                }
+                Err(_) => report.errors.push(VrmValidationIssue::InvalidNodeIndex {
+                    referrer: "firstPerson mesh annotation".to_string(),
+                    index: usize::MAX,
+                }),
+            }
+        }
+    }
+
+    fn check_spring_bone(
+        &self,
+        spring_bone: &VRMCSpringBone,
+        report: &mut VrmValidationReport,
+    ) {
+        for (i, collider) in spring_bone.colliders.iter().enumerate() {
+            self.check_node(format!("collider {i}"), collider.node, report);
+        }
+        for group in &spring_bone.collider_groups {
+            for &index in &group.colliders {
+                if spring_bone.colliders.len() as u64 <= index {
+                    report
+                        .errors
+                        .push(VrmValidationIssue::InvalidColliderIndex {
+                            referrer: format!("collider group {}", group.name),
+                            index: usize::try_from(index).unwrap_or(usize::MAX),
+                        });
+                }
+            }
+        }
+        for spring in &spring_bone.springs {
+            let referrer = format!("spring {}", spring.name);
+            for joint in &spring.joints {
+                self.check_node(referrer.clone(), joint.node, report);
+            }
+            if let Some(center) = spring.center {
+                self.check_node(referrer.clone(), center, report);
+            }
+            for &index in spring.collider_groups.iter().flatten() {
+                if spring_bone.collider_groups.len() <= index {
+                    report
+                        .errors
+                        .push(VrmValidationIssue::InvalidColliderGroupIndex {
+                            referrer: referrer.clone(),
+                            index,
+                        });
+                }
+            }
+            if spring.joints.len() < 2 {
+                report
+                    .warnings
+                    .push(VrmValidationIssue::TooFewSpringJoints(spring.name.clone()));
+            }
+        }
+    }
+
+    /// Reports an error and returns `false` if the node index is out of range.
+    fn check_node(
+        &self,
+        referrer: String,
+        index: usize,
+        report: &mut VrmValidationReport,
+    ) -> bool {
+        let exists = index < self.document.nodes.len();
+        if !exists {
+            report
+                .errors
+                .push(VrmValidationIssue::InvalidNodeIndex { referrer, index });
+        }
+        exists
+    }
+
+    fn is_descendant(
+        &self,
+        node: usize,
+        ancestor: usize,
+    ) -> bool {
+        // The depth is bounded by the node count, which also guards against cyclic hierarchies.
+        let mut current = node;
+        for _ in 0..self.parents.len() {
+            let Some(parent) = self.parents.get(current).copied().flatten() else {
+                return false;
+            };
+            if parent == ancestor {
+                return true;
+            }
+            current = parent;
+        }
+        false
+    }
+}
+
+fn node_parents(document: &GltfDocument) -> Vec<Option<usize>> {
+    let mut parents = vec![None; document.nodes.len()];
+    for (index, node) in document.nodes.iter().enumerate() {
+        for &child in &node.children {
+            if let Some(parent) = parents.get_mut(child) {
+                *parent = Some(index);
+            }
+        }
+    }
+    parents
+}
+
+/// Returns the parent bone of the humanoid bone defined in the specification.
+///
+/// Please see [`humanoid`](https://github.com/vrm-c/vrm-specification/blob/master/specification/VRMC_vrm-1.0/humanoid.md) for details.
+fn humanoid_parent(bone: &str) -> Option<String> {
+    let (side, part) = bone
+        .strip_prefix("left")
+        .map(|part| ("left", part))
+        .or_else(|| bone.strip_prefix("right").map(|part| ("right", part)))
+        .unwrap_or(("", bone));
+    let sided = |parent: &str| Some(format!("{side}{parent}"));
+    let center = |parent: &str| Some(parent.to_string());
+    match (side, part) {
+        ("", "spine") | (_, "UpperLeg") => center("hips"),
+        ("", "chest") => center("spine"),
+        ("", "upperChest") => center("chest"),
+        ("", "neck") | (_, "Shoulder") => center("upperChest"),
+        ("", "head") => center("neck"),
+        ("", "jaw") | (_, "Eye") => center("head"),
+        (_, "LowerLeg") => sided("UpperLeg"),
+        (_, "Foot") => sided("LowerLeg"),
+        (_, "Toes") => sided("Foot"),
+        (_, "UpperArm") => sided("Shoulder"),
+        (_, "LowerArm") => sided("UpperArm"),
+        (_, "Hand") => sided("LowerArm"),
+        (
+            _,
+            "ThumbMetacarpal" | "IndexProximal" | "MiddleProximal" | "RingProximal"
+            | "LittleProximal",
+        ) => sided("Hand"),
+        (_, "ThumbProximal") => sided("ThumbMetacarpal"),
+        (_, "ThumbDistal") => sided("ThumbProximal"),
+        ("", _) => None,
+        (_, finger) => {
+            if let Some(finger) = finger.strip_suffix("Intermediate") {
+                sided(&format!("{finger}Proximal"))
+            } else {
+                let finger = finger.strip_suffix("Distal")?;
+                sided(&format!("{finger}Intermediate"))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrm::gltf::extensions::vrm0::GltfDocument;
+    use crate::vrm::gltf::extensions::VrmExtensions;
+    use crate::vrm::validator::{
+        humanoid_parent, VrmValidationIssue, VrmValidationReport, VrmValidator,
+    };
+    use serde::Deserialize;
+
+    #[test]
+    fn humanoid_parents() {
+        assert_eq!(humanoid_parent("hips"), None);
+        assert_eq!(humanoid_parent("head").as_deref(), Some("neck"));
+        assert_eq!(humanoid_parent("leftEye").as_deref(), Some("head"));
+        assert_eq!(humanoid_parent("rightUpperLeg").as_deref(), Some("hips"));
+        assert_eq!(
+            humanoid_parent("leftRingDistal").as_deref(),
+            Some("leftRingIntermediate")
+        );
+        assert_eq!(humanoid_parent("tail"), None);
+    }
+
+    #[test]
+    fn report_invalid_references() -> TestResult {
+        let root: serde_json::Value = serde_json::from_str(include_str!("validator.json"))?;
+        let document = GltfDocument::deserialize(&root)?;
+        let extensions = VrmExtensions::parse_document(
+            root["extensions"].as_object().ok_or("no extensions")?,
+            &document,
+        )?;
+        let report = VrmValidator::validate_document(&extensions, &document);
+
+        assert_eq!(
+            report.errors,
+            vec![
+                VrmValidationIssue::MissingHumanoidBone("rightHand".to_string()),
+                VrmValidationIssue::InvalidBoneHierarchy {
+                    bone: "leftHand".to_string(),
+                    parent: "leftLowerArm".to_string(),
+                },
+                VrmValidationIssue::InvalidMorphTargetIndex {
+                    expression: "aa".to_string(),
+                    index: 2,
+                    count: 2,
+                },
+                VrmValidationIssue::InvalidColliderIndex {
+                    referrer: "collider group head".to_string(),
+                    index: 1,
+                },
+                VrmValidationIssue::InvalidColliderGroupIndex {
+                    referrer: "spring hair".to_string(),
+                    index: 3,
+                },
+            ]
+        );
+        assert_eq!(
+            report.warnings,
+            vec![VrmValidationIssue::NewerSpecVersion {
+                extension: "VRMC_vrm",
+                version: "1.1".to_string(),
+            }]
+        );
+        success!()
+    }
+
+    #[test]
+    fn load_only_without_fatal_errors() {
+        let report = VrmValidationReport {
+            errors: vec![VrmValidationIssue::InvalidBoneHierarchy {
+                bone: "leftHand".to_string(),
+                parent: "leftLowerArm".to_string(),
+            }],
+            warnings: vec![VrmValidationIssue::MissingMeta],
+        };
+        assert!(!report.is_valid());
+        assert!(report.is_loadable());
+
+        for fatal in [
+            VrmValidationIssue::InvalidNodeIndex {
+                referrer: "humanoid bone head".to_string(),
+                index: 100,
+            },
+            VrmValidationIssue::MissingHumanoidBone("hips".to_string()),
+            VrmValidationIssue::UnsupportedSpecVersion {
+                extension: "VRMC_vrm",
+                version: "2.0".to_string(),
+            },
+        ] {
+            let mut report = report.clone();
+            report.errors.push(fatal);
+            assert!(!report.is_loadable());
+        }
+    }
+}
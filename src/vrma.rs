@@ -3,6 +3,7 @@ mod gltf;
 mod initialize;
 mod loader;
 
+use crate::error::VrmError;
 use crate::macros::{entity_component, marker_component};
 use crate::vrma::animation::VrmaAnimationPlayersPlugin;
 use crate::vrma::initialize::VrmaInitializePlugin;
@@ -15,8 +16,10 @@ use std::time::Duration;
 
 pub mod prelude {
     pub use crate::vrma::{
-        animation::prelude::*, loader::VrmaAsset, LoadedVrma, Vrma, VrmaDuration, VrmaEntity,
-        VrmaHandle, VrmaPath, VrmaPlugin,
+        animation::prelude::*,
+        loader::{VrmaAsset, VrmaLoaderSettings},
+        LoadedVrma, Vrma, VrmaDuration, VrmaEntity, VrmaHandle, VrmaLoadFailed, VrmaPath,
+        VrmaPlugin,
     };
 }
 
@@ -102,10 +105,31 @@ pub struct LoadedVrma {
     pub vrm: Entity,
 }
 
+/// An event that is emitted when VRMA could not be spawned from [`VrmaHandle`].
+///
+/// This is emitted when the asset fails to load, e.g. the VRMA does not have a valid `VRMC_vrm_animation`,
+/// and [`VrmError::Load`](crate::prelude::VrmError::Load) contains the error returned by the loader.
+///
+/// This event is emitted as a trigger.
+/// The target of the trigger is the entity that had [`VrmaHandle`].
+#[derive(Debug, Event)]
+pub struct VrmaLoadFailed {
+    pub vrm: Entity,
+    pub error: VrmError,
+}
+
 /// The component that holds the animation clip of VRMA.
+///
+/// The clip is rewritten to target the bones of the VRM,
+/// so each VRMA entity retargets its own copy of the source clip.
 #[derive(Debug, Component, Reflect)]
 #[reflect(Component)]
-pub(crate) struct VrmAnimationClipHandle(pub Handle<AnimationClip>);
+pub(crate) struct VrmAnimationClipHandle {
+    /// The clip loaded from the VRMA file.
+    pub source: Handle<AnimationClip>,
+    /// The clip retargeted to the VRM.
+    pub retargeted: Handle<AnimationClip>,
+}
 
 /// The component that holds the animation node index for VRMA.
 #[derive(Debug, Component, Reflect, Copy, Clone, Default)]
@@ -2,12 +2,15 @@ pub(crate) mod animation_graph;
 mod bone_rotation;
 mod bone_translation;
 pub(crate) mod expressions;
+pub(crate) mod look_at;
 mod play;
 
 use crate::vrma::animation::animation_graph::VrmaAnimationGraphPlugin;
 use crate::vrma::animation::expressions::VrmaRetargetExpressionsPlugin;
+use crate::vrma::animation::look_at::VrmaRetargetLookAtPlugin;
 use crate::vrma::animation::play::VrmaAnimationPlayPlugin;
-use crate::vrma::RetargetSource;
+use crate::vrma::{RetargetSource, VrmAnimationClipHandle, VrmAnimationNodeIndex};
+use bevy::animation::AnimationTarget;
 use bevy::app::App;
 use bevy::prelude::*;
 use bevy::window::RequestRedraw;
@@ -31,6 +34,7 @@ impl Plugin for VrmaAnimationPlayersPlugin {
                 VrmaAnimationGraphPlugin,
                 VrmaAnimationPlayPlugin,
                 VrmaRetargetExpressionsPlugin,
+                VrmaRetargetLookAtPlugin,
             ))
             .add_systems(Update, request_redraw.run_if(playing_animation));
     }
@@ -53,3 +57,28 @@ fn playing_animation(
 fn request_redraw(mut request: EventWriter<RequestRedraw>) {
     request.write(RequestRedraw);
 }
+
+/// Returns `true` if the VRMA playing on the VRM animates the target.
+pub(crate) fn is_animated_by_vrma(
+    vrm: Entity,
+    target: &AnimationTarget,
+    players: &Query<&AnimationPlayer>,
+    vrmas: &Query<(&VrmAnimationNodeIndex, &VrmAnimationClipHandle, &ChildOf)>,
+    clips: &Assets<AnimationClip>,
+) -> bool {
+    let Ok(player) = players.get(target.player) else {
+        return false;
+    };
+    player
+        .playing_animations()
+        .filter(|(_, animation)| !animation.is_finished())
+        .any(|(node_index, _)| {
+            vrmas.iter().any(|(index, handle, ChildOf(parent))| {
+                *parent == vrm
+                    && index.0 == *node_index
+                    && clips
+                        .get(&handle.retargeted)
+                        .is_some_and(|clip| clip.curves().contains_key(&target.id))
+            })
+        })
+}
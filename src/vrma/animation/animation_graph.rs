@@ -1,10 +1,12 @@
-use crate::prelude::{BoneRestGlobalTransform, BoneRestTransform, ChildSearcher};
+use crate::prelude::{BoneRestGlobalTransform, BoneRestTransform, ChildSearcher, Vrm};
 use crate::vrm::expressions::VrmExpressionRegistry;
 use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
 use crate::vrma::animation::bone_rotation::{
     BoneRotateTransformations, BoneRotationAnimationCurve,
 };
 use crate::vrma::animation::bone_translation::HipsTranslationAnimationCurve;
+use crate::vrma::animation::expressions::VrmaExpressionNames;
+use crate::vrma::animation::look_at::VrmaLookAtName;
 use crate::vrma::{VrmAnimationClipHandle, VrmAnimationNodeIndex};
 use bevy::animation::{animated_field, AnimationTarget};
 use bevy::app::App;
@@ -28,7 +30,8 @@ impl Plugin for VrmaAnimationGraphPlugin {
     ) {
         app.add_observer(apply_animation_graph)
             .add_observer(apply_replace_humanoid_bone_animation_clips)
-            .add_observer(apply_regenerate_expression_clips);
+            .add_observer(apply_regenerate_expression_clips)
+            .add_observer(apply_replace_look_at_clips);
     }
 }
 
@@ -36,6 +39,7 @@ fn apply_animation_graph(
     trigger: Trigger<RequestUpdateAnimationGraph>,
     mut commands: Commands,
     mut graphs: ResMut<Assets<AnimationGraph>>,
+    mut clips: ResMut<Assets<AnimationClip>>,
     childrens: Query<&Children>,
     vrmas: Query<(Entity, &VrmAnimationClipHandle)>,
     child_searcher: ChildSearcher,
@@ -46,6 +50,13 @@ fn apply_animation_graph(
     let Ok(children) = childrens.get(vrm_entity) else {
         return;
     };
+    // Retargeting rewrites the clip, so it restarts from the source clip
+    // in case the VRMA has already been retargeted to the previous scene of the VRM.
+    if let Ok((_, clip_handle)) = vrmas.get(vrma_entity) {
+        if let Some(source) = clips.get(&clip_handle.source).cloned() {
+            clips.insert(&clip_handle.retargeted, source);
+        }
+    }
     let animation_graph = generate_animation_graph(&mut commands, &vrmas, children);
     let animation_graph_handle = AnimationGraphHandle(graphs.add(animation_graph));
     insert_animation_graph_into_root_bone(
@@ -77,7 +88,8 @@ fn generate_animation_graph(
         .iter()
         .flat_map(|child| vrmas_query.get(child).ok())
         .collect::<Vec<_>>();
-    let (graph, nodes) = AnimationGraph::from_clips(vrmas.iter().map(|(_, h)| h.0.clone()));
+    let (graph, nodes) =
+        AnimationGraph::from_clips(vrmas.iter().map(|(_, h)| h.retargeted.clone()));
     for (i, (entity, _)) in vrmas.iter().enumerate() {
         commands
             .entity(*entity)
@@ -154,7 +166,7 @@ fn apply_replace_humanoid_bone_animation_clips(
     let Some(root_bone) = searcher.find_root_bone(*vrm_entity) else {
         return;
     };
-    let Some(clip) = clips.get_mut(vrm_animation_clip_handle.0.id()) else {
+    let Some(clip) = clips.get_mut(&vrm_animation_clip_handle.retargeted) else {
         return;
     };
     let transformations =
@@ -260,6 +272,7 @@ fn apply_regenerate_expression_clips(
     clip_handles: Query<&VrmAnimationClipHandle>,
     animation_targets: Query<&AnimationTarget>,
     expressions: Query<&VrmExpressionRegistry>,
+    vrma_expressions: Query<&VrmaExpressionNames>,
     searcher: ChildSearcher,
     parents: Query<&ChildOf>,
 ) {
@@ -273,14 +286,20 @@ fn apply_regenerate_expression_clips(
     let Ok(vrm_animation_clip_handle) = clip_handles.get(vrma_entity) else {
         return;
     };
-    let Some(clip) = clips.get_mut(vrm_animation_clip_handle.0.id()) else {
+    let Some(clip) = clips.get_mut(&vrm_animation_clip_handle.retargeted) else {
         return;
     };
     let Ok(registry) = expressions.get(vrm_entity) else {
         return;
     };
+    let Ok(vrma_expressions) = vrma_expressions.get(vrma_entity) else {
+        return;
+    };
     for (expression, _) in registry.iter() {
-        let Some(vrma_expression) = searcher.find_from_name(vrma_entity, expression) else {
+        let Some(node_name) = vrma_expressions.get(expression) else {
+            continue;
+        };
+        let Some(vrma_expression) = searcher.find_from_name(vrma_entity, node_name) else {
             continue;
         };
         let Some(expression_entity) = searcher.find_from_name(expressions_root, expression) else {
@@ -299,5 +318,63 @@ fn apply_regenerate_expression_clips(
     }
 }
 
+fn apply_replace_look_at_clips(
+    trigger: Trigger<RequestUpdateAnimationClips>,
+    mut clips: ResMut<Assets<AnimationClip>>,
+    clip_handles: Query<&VrmAnimationClipHandle>,
+    animation_targets: Query<&AnimationTarget>,
+    vrma_look_ats: Query<&VrmaLookAtName>,
+    searcher: ChildSearcher,
+    parents: Query<&ChildOf>,
+) {
+    let vrma_entity = trigger.target();
+    let Ok(vrm_entity) = parents.get(vrma_entity).map(|c| c.parent()) else {
+        return;
+    };
+    let Ok(node_name) = vrma_look_ats.get(vrma_entity) else {
+        return;
+    };
+    let Some(vrma_look_at) = searcher.find_from_name(vrma_entity, node_name) else {
+        return;
+    };
+    let Some(source) = searcher
+        .find_root_bone(vrm_entity)
+        .and_then(|root_bone| searcher.find_from_name(root_bone, Vrm::LOOK_AT_SOURCE))
+    else {
+        return;
+    };
+    let Ok(vrma_target) = animation_targets.get(vrma_look_at) else {
+        return;
+    };
+    let Ok(target) = animation_targets.get(source) else {
+        return;
+    };
+    let Ok(vrm_animation_clip_handle) = clip_handles.get(vrma_entity) else {
+        return;
+    };
+    let Some(clip) = clips.get_mut(&vrm_animation_clip_handle.retargeted) else {
+        return;
+    };
+    let animation_curves = clip.curves_mut();
+    if let Some(curves) = animation_curves.remove(&vrma_target.id) {
+        // Only the translation represents the point looked at.
+        let translation_field = animated_field!(Transform::translation);
+        let EvaluatorId::ComponentField(translation_component) = translation_field.evaluator_id()
+        else {
+            return;
+        };
+        let curves = curves
+            .into_iter()
+            .filter(|curve| {
+                matches!(
+                    curve.0.evaluator_id(),
+                    EvaluatorId::ComponentField(component) if component == translation_component
+                )
+            })
+            .collect::<Vec<_>>();
+        animation_curves.insert(target.id, curves);
+    }
+}
+
 #[cfg(test)]
 mod tests {}
@@ -1,11 +1,11 @@
 //!  This module handles the retargeting of expressions from a VRM model to a mascot model.
+//!
+//! The retargeted weights are applied to the morph targets in [`crate::vrm::expressions`].
 
-use crate::system_set::VrmSystemSets;
-use crate::vrm::expressions::{BindExpressionNode, RetargetExpressionNodes};
 use crate::vrm::VrmExpression;
 use crate::vrma::gltf::extensions::VrmaExtensions;
-use bevy::app::{Animation, App};
-use bevy::prelude::TransformSystem::TransformPropagate;
+use bevy::app::App;
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 
 pub(in crate::vrma) struct VrmaRetargetExpressionsPlugin;
@@ -15,53 +15,34 @@ impl Plugin for VrmaRetargetExpressionsPlugin {
         &self,
         app: &mut App,
     ) {
-        app.register_type::<RetargetExpressionNodes>()
-            .register_type::<BindExpressionNode>()
-            .add_systems(
-                PostUpdate,
-                bind_expressions
-                    .in_set(VrmSystemSets::Retarget)
-                    .after(Animation)
-                    .after(TransformPropagate),
-            );
+        app.register_type::<VrmaExpressionNames>();
     }
 }
 
+/// Holds the names of the nodes animating each expression, including the custom expressions.
 #[derive(Component, Deref, Reflect)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
-pub(crate) struct VrmaExpressionNames(Vec<VrmExpression>);
+pub(crate) struct VrmaExpressionNames(HashMap<VrmExpression, Name>);
 
 impl VrmaExpressionNames {
-    pub fn new(extensions: &VrmaExtensions) -> Self {
+    pub fn new(
+        extensions: &VrmaExtensions,
+        node_names: &[Name],
+    ) -> Self {
         let Some(expressions) = extensions.vrmc_vrm_animation.expressions.as_ref() else {
-            return Self(Vec::default());
+            return Self(HashMap::default());
         };
         Self(
             expressions
                 .preset
-                .keys()
-                .map(|expression| VrmExpression(expression.clone()))
+                .iter()
+                .chain(expressions.custom.iter())
+                .filter_map(|(expression, node)| {
+                    let node_name = node_names.get(node.node)?;
+                    Some((VrmExpression(expression.clone()), node_name.clone()))
+                })
                 .collect(),
         )
     }
 }
-
-fn bind_expressions(
-    mut expressions: Query<&mut MorphWeights>,
-    rig_expressions: Query<(&Transform, &RetargetExpressionNodes), Changed<Transform>>,
-) {
-    for (tf, RetargetExpressionNodes(binds)) in rig_expressions.iter() {
-        // VRMA uses x coordinate to represent expression weight.
-        let weight = tf.translation.x;
-        for BindExpressionNode {
-            expression_entity,
-            index,
-        } in binds.iter()
-        {
-            if let Ok(mut morph_weights) = expressions.get_mut(*expression_entity) {
-                morph_weights.weights_mut()[*index] = weight;
-            }
-        }
-    }
-}
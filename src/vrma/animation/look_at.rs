@@ -0,0 +1,36 @@
+//! This module handles the retargeting of the `lookAt` node of VRMA.
+//!
+//! The translation of the node is moved to the look-at source under the root bone of the VRM,
+//! and the eyes look at that point in [`crate::vrm::look_at`].
+
+use crate::vrma::gltf::extensions::VrmaExtensions;
+use bevy::app::App;
+use bevy::prelude::*;
+
+pub(in crate::vrma) struct VrmaRetargetLookAtPlugin;
+
+impl Plugin for VrmaRetargetLookAtPlugin {
+    fn build(
+        &self,
+        app: &mut App,
+    ) {
+        app.register_type::<VrmaLookAtName>();
+    }
+}
+
+/// Holds the name of the node animating the gaze direction.
+#[derive(Component, Deref, Reflect)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", reflect(Serialize, Deserialize))]
+pub(crate) struct VrmaLookAtName(Name);
+
+impl VrmaLookAtName {
+    /// Returns `None` if the VRMA does not animate the gaze.
+    pub fn new(
+        extensions: &VrmaExtensions,
+        node_names: &[Name],
+    ) -> Option<Self> {
+        let look_at = extensions.vrmc_vrm_animation.look_at?;
+        node_names.get(look_at.node).cloned().map(Self)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::prelude::ChildSearcher;
+use crate::vrm::expressions::ExpressionCrossfade;
 use crate::vrma::VrmAnimationNodeIndex;
 use bevy::animation::{AnimationPlayer, RepeatAnimation};
 use bevy::app::{App, Plugin};
@@ -59,6 +60,7 @@ fn apply_play_vrma(
     )>,
     searcher: ChildSearcher,
     parents: Query<&ChildOf>,
+    mut crossfades: Query<&mut ExpressionCrossfade>,
     childrens: Query<&Children>,
     vrmas: Query<&VrmAnimationNodeIndex>,
 ) {
@@ -81,7 +83,9 @@ fn apply_play_vrma(
         *vrm_entity,
         node_index.0,
         trigger.repeat,
+        trigger.transition_duration,
         &mut players,
+        &mut crossfades,
         &childrens,
         &searcher,
     );
@@ -114,11 +118,13 @@ fn play_expression_animations(
     vrm: Entity,
     node_index: AnimationNodeIndex,
     repeat: RepeatAnimation,
+    transition_duration: Duration,
     entities: &mut Query<(
         &mut Transform,
         &mut AnimationPlayer,
         Option<&mut AnimationTransitions>,
     )>,
+    crossfades: &mut Query<&mut ExpressionCrossfade>,
     childrens: &Query<&Children>,
     searcher: &ChildSearcher,
 ) {
@@ -130,7 +136,11 @@ fn play_expression_animations(
     };
     for child in children.iter().copied() {
         if let Ok((mut tf, mut player, _)) = entities.get_mut(child) {
-            // Reset the expression weight to zero.
+            // The weight is reset to zero for the expressions that the next VRMA does not animate,
+            // and the current weight fades out over the transition.
+            if let Ok(mut crossfade) = crossfades.get_mut(child) {
+                crossfade.start(tf.translation.x, transition_duration);
+            }
             tf.translation.x = 0.0;
             player.stop_all();
             player.play(node_index).set_repeat(repeat);
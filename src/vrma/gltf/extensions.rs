@@ -1,14 +1,21 @@
 //! This module defines the data structures for VRMA extensions in the GLTF format.
 
-use crate::error::AppResult;
-use crate::vrm::gltf::extensions::{obtain_extensions, obtain_vrmc_vrm, VrmNode};
+use crate::error::VrmError;
+use crate::vrm::gltf::extensions::{
+    check_node_indices, check_spec_version, obtain_extensions, parse_extension, VrmNode,
+};
 use bevy::gltf::Gltf;
 use bevy::platform::collections::HashMap;
 use serde::{Deserialize, Serialize};
 
+const VRMC_VRM_ANIMATION: &str = "VRMC_vrm_animation";
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub(crate) struct VrmaExpressions {
+    #[serde(default)]
     pub preset: HashMap<String, VrmNode>,
+    #[serde(default)]
+    pub custom: HashMap<String, VrmNode>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -17,28 +24,71 @@ pub(crate) struct VrmaHumanoid {
     pub human_bones: HashMap<String, VrmNode>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct VRMCVrmAnimation {
     pub expressions: Option<VrmaExpressions>,
     pub humanoid: VrmaHumanoid,
+    /// The node whose rotation animates the gaze direction.
+    #[serde(rename = "lookAt")]
+    pub look_at: Option<VrmNode>,
     #[serde(rename = "specVersion")]
     pub spec_version: Option<String>,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, Debug)]
 pub(crate) struct VrmaExtensions {
     #[serde(rename = "VRMC_vrm_animation")]
     pub vrmc_vrm_animation: VRMCVrmAnimation,
 }
 
 impl VrmaExtensions {
-    pub fn new(json: &serde_json::map::Map<String, serde_json::Value>) -> AppResult<Self> {
-        Ok(Self {
-            vrmc_vrm_animation: serde_json::from_value(obtain_vrmc_vrm(json)?)?,
-        })
+    pub fn new(json: &serde_json::map::Map<String, serde_json::Value>) -> Result<Self, VrmError> {
+        let vrmc_vrm_animation: VRMCVrmAnimation = parse_extension(json, VRMC_VRM_ANIMATION)?;
+        if let Some(version) = vrmc_vrm_animation.spec_version.as_ref() {
+            check_spec_version(VRMC_VRM_ANIMATION, version)?;
+        }
+        Ok(Self { vrmc_vrm_animation })
     }
 
-    pub fn from_gltf(gltf: &Gltf) -> AppResult<Self> {
-        Self::new(obtain_extensions(gltf)?)
+    pub fn from_gltf(gltf: &Gltf) -> Result<Self, VrmError> {
+        let json = obtain_extensions(gltf).ok_or(VrmError::MissingExtension(VRMC_VRM_ANIMATION))?;
+        let extensions = Self::new(json)?;
+        check_node_indices(
+            &extensions.vrmc_vrm_animation.humanoid.human_bones,
+            gltf.nodes.len(),
+        )?;
+        if let Some(look_at) = extensions.vrmc_vrm_animation.look_at {
+            if gltf.nodes.len() <= look_at.node {
+                return Err(VrmError::InvalidNodeIndex {
+                    referrer: "lookAt".to_string(),
+                    index: look_at.node,
+                });
+            }
+        }
+        Ok(extensions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::TestResult;
+    use crate::vrma::gltf::extensions::VrmaExtensions;
+
+    #[test]
+    fn parse_look_at() -> TestResult {
+        let json = serde_json::json!({
+            "VRMC_vrm_animation": {
+                "specVersion": "1.0",
+                "humanoid": { "humanBones": { "hips": { "node": 1 } } },
+                "lookAt": { "node": 2, "offsetFromHeadBone": [0.0, 0.06, 0.0] }
+            }
+        });
+        let extensions = VrmaExtensions::new(json.as_object().unwrap())?;
+        assert_eq!(
+            extensions.vrmc_vrm_animation.look_at.map(|n| n.node),
+            Some(2)
+        );
+        success!()
     }
 }
@@ -1,13 +1,16 @@
 //! This module inserts [`SceneRoot`] and VRMA-related components from the loaded [`VrmaHandle`].
 
-use crate::error::vrm_error;
+use crate::error::{vrm_error, VrmError};
+use crate::vrm::gltf::node_names;
 use crate::vrm::humanoid_bone::HumanoidBoneRegistry;
 use crate::vrm::Initialized;
 use crate::vrma::animation::expressions::VrmaExpressionNames;
-use crate::vrma::gltf::extensions::VrmaExtensions;
+use crate::vrma::animation::look_at::VrmaLookAtName;
 use crate::vrma::loader::VrmaAsset;
-use crate::vrma::{LoadedVrma, VrmAnimationClipHandle, Vrma, VrmaDuration, VrmaHandle, VrmaPath};
-use bevy::gltf::GltfNode;
+use crate::vrma::{
+    LoadedVrma, VrmAnimationClipHandle, Vrma, VrmaDuration, VrmaHandle, VrmaLoadFailed, VrmaPath,
+};
+use bevy::asset::LoadState;
 use bevy::prelude::*;
 use bevy::scene::SceneRoot;
 use std::time::Duration;
@@ -25,9 +28,9 @@ impl Plugin for VrmaInitializePlugin {
 
 fn spawn_vrma(
     mut commands: Commands,
+    asset_server: Res<AssetServer>,
     vrma_assets: Res<Assets<VrmaAsset>>,
-    node_assets: Res<Assets<GltfNode>>,
-    clip_assets: Res<Assets<AnimationClip>>,
+    mut clip_assets: ResMut<Assets<AnimationClip>>,
     vrma_handles: Query<(Entity, &VrmaHandle, &ChildOf)>,
     vrms: Query<Has<Initialized>>,
 ) {
@@ -44,43 +47,69 @@ fn spawn_vrma(
         let Some(name) = handle.0.path().map(|p| p.to_string()) else {
             continue;
         };
+        let vrm = child_of.parent();
         let Some(vrma) = vrma_assets.get(handle.0.id()) else {
+            if let Some(LoadState::Failed(e)) = asset_server.get_load_state(handle.0.id()) {
+                trigger_load_failed(&mut commands, handle_entity, vrm, VrmError::Load(e));
+            }
             continue;
         };
-        commands.entity(handle_entity).remove::<VrmaHandle>();
 
-        let Some(scene_root) = vrma.gltf.scenes.first().cloned() else {
-            vrm_error!("[VRMA] Not found vrma scene in {name}");
+        // The loader has checked the scene, the animation and the extensions.
+        let settings = &vrma.settings;
+        let extensions = &vrma.extensions;
+        let Some(scene_root) = vrma.gltf.scenes.get(settings.scene).cloned() else {
             continue;
         };
-        let extensions = match VrmaExtensions::from_gltf(&vrma.gltf) {
-            Ok(extensions) => extensions,
-            Err(_e) => {
-                vrm_error!("[VRMA] Not found vrma extensions in {name}:\n{_e}");
-                continue;
-            }
+        let Some(animation_clip_handle) = vrma.gltf.animations.get(settings.animation) else {
+            continue;
         };
-        let Some(animation_clip_handle) = vrma.gltf.animations.first() else {
-            vrm_error!("[VRMA] Not found vrma animations in {name}");
+        let Some(clip) = clip_assets.get(animation_clip_handle).cloned() else {
             continue;
         };
-        commands.entity(handle_entity).insert((
-            Vrma,
-            Name::new(name),
-            VrmAnimationClipHandle(animation_clip_handle.clone()),
-            SceneRoot(scene_root),
-            VrmaDuration(obtain_vrma_duration(&clip_assets, &vrma.gltf.animations)),
-            VrmaPath(vrma_path),
-            VrmaExpressionNames::new(&extensions),
-            HumanoidBoneRegistry::new(
-                &extensions.vrmc_vrm_animation.humanoid.human_bones,
-                &node_assets,
-                &vrma.gltf.nodes,
-            ),
-        ));
+        let retargeted_clip_handle = clip_assets.add(clip);
+        let node_names = node_names(&vrma.gltf);
+        commands
+            .entity(handle_entity)
+            .remove::<VrmaHandle>()
+            .insert((
+                Vrma,
+                Name::new(name),
+                VrmAnimationClipHandle {
+                    source: animation_clip_handle.clone(),
+                    retargeted: retargeted_clip_handle,
+                },
+                SceneRoot(scene_root),
+                VrmaDuration(obtain_vrma_duration(
+                    &clip_assets,
+                    std::slice::from_ref(animation_clip_handle),
+                )),
+                VrmaPath(vrma_path),
+                VrmaExpressionNames::new(extensions, &node_names),
+                HumanoidBoneRegistry::new(
+                    &extensions.vrmc_vrm_animation.humanoid.human_bones,
+                    &node_names,
+                ),
+            ));
+        if let Some(look_at) = VrmaLookAtName::new(extensions, &node_names) {
+            commands.entity(handle_entity).insert(look_at);
+        }
     }
 }
 
+fn trigger_load_failed(
+    commands: &mut Commands,
+    entity: Entity,
+    vrm: Entity,
+    error: VrmError,
+) {
+    vrm_error!("[VRMA] Failed to spawn VRMA", &error);
+    commands
+        .entity(entity)
+        .remove::<VrmaHandle>()
+        .trigger(VrmaLoadFailed { vrm, error });
+}
+
 fn obtain_vrma_duration(
     assets: &Assets<AnimationClip>,
     handles: &[Handle<AnimationClip>],
@@ -1,13 +1,16 @@
 //! This module provides the functionality to load VRMA files.
 
+use crate::error::VrmError;
+use crate::vrma::gltf::extensions::VrmaExtensions;
 use bevy::app::{App, Plugin};
 use bevy::asset::io::Reader;
 use bevy::asset::{Asset, AssetLoader, LoadContext};
-use bevy::gltf::{Gltf, GltfError, GltfLoader, GltfLoaderSettings};
+use bevy::gltf::{Gltf, GltfLoader, GltfLoaderSettings};
 use bevy::image::CompressedImageFormats;
 use bevy::prelude::*;
 use bevy::render::renderer::RenderDevice;
 use bevy::utils::default;
+use serde::{Deserialize, Serialize};
 
 pub(super) struct VrmaLoaderPlugin;
 
@@ -38,6 +41,9 @@ impl Plugin for VrmaLoaderPlugin {
 /// Represents a VRMA asset.
 /// You can load it using [`AssetServer`].
 ///
+/// `VRMC_vrm_animation` is parsed and checked when the asset is loaded,
+/// so a malformed VRMA fails to load with [`VrmError`].
+///
 ///```no_run
 /// use bevy::prelude::*;
 /// use bevy_vrm1::prelude::*;
@@ -53,29 +59,108 @@ impl Plugin for VrmaLoaderPlugin {
 #[derive(Debug, Asset, TypePath)]
 pub struct VrmaAsset {
     pub gltf: Gltf,
+    pub settings: VrmaLoaderSettings,
+    pub(crate) extensions: VrmaExtensions,
+}
+
+/// The settings to load a VRMA through [`AssetServer::load_with_settings`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct VrmaLoaderSettings {
+    /// The index of the glTF scene to spawn.
+    pub scene: usize,
+    /// The index of the glTF animation to play.
+    pub animation: usize,
 }
 
 struct VrmaLoader(GltfLoader);
 
 impl AssetLoader for VrmaLoader {
     type Asset = VrmaAsset;
-    type Settings = ();
-    type Error = GltfError;
+    type Settings = VrmaLoaderSettings;
+    type Error = VrmError;
     async fn load(
         &self,
         reader: &mut dyn Reader,
-        _: &Self::Settings,
+        settings: &Self::Settings,
         load_context: &mut LoadContext<'_>,
     ) -> Result<Self::Asset, Self::Error> {
-        let settings = GltfLoaderSettings {
+        let gltf_settings = GltfLoaderSettings {
             include_source: true,
             ..default()
         };
-        let gltf = self.0.load(reader, &settings, load_context).await?;
-        Ok(VrmaAsset { gltf })
+        let gltf = self.0.load(reader, &gltf_settings, load_context).await?;
+        if gltf.scenes.get(settings.scene).is_none() {
+            return Err(VrmError::MissingScene(settings.scene));
+        }
+        if gltf.animations.get(settings.animation).is_none() {
+            return Err(VrmError::MissingAnimation(settings.animation));
+        }
+        let extensions = VrmaExtensions::from_gltf(&gltf)?;
+        Ok(VrmaAsset {
+            gltf,
+            settings: settings.clone(),
+            extensions,
+        })
     }
 
     fn extensions(&self) -> &[&str] {
         &["vrma"]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::success;
+    use crate::tests::{test_asset_app, wait_for_load, TestResult};
+    use crate::vrma::loader::{VrmaAsset, VrmaLoaderPlugin};
+    use bevy::asset::AssetServer;
+
+    /// Replaces the VRM extensions of the fixture with `VRMC_vrm_animation`.
+    fn fixture() -> serde_json::Value {
+        let mut json: serde_json::Value =
+            serde_json::from_str(include_str!("../vrm/loader.json")).unwrap();
+        json["extensions"] = serde_json::json!({
+            "VRMC_vrm_animation": {
+                "specVersion": "1.0",
+                "humanoid": { "humanBones": { "hips": { "node": 0 } } }
+            }
+        });
+        json
+    }
+
+    fn load_vrma(json: &serde_json::Value) -> Result<(), String> {
+        let bytes = serde_json::to_vec(json).map_err(|e| e.to_string())?;
+        let mut app = test_asset_app(&[("motion.vrma", &bytes)]);
+        app.add_plugins(VrmaLoaderPlugin);
+        app.finish();
+        let handle = app
+            .world()
+            .resource::<AssetServer>()
+            .load::<VrmaAsset>("memory://motion.vrma");
+        wait_for_load(&mut app, &handle)
+    }
+
+    #[test]
+    fn refuse_invalid_vrmc_vrm_animation() -> TestResult {
+        let mut json = fixture();
+        load_vrma(&json)?;
+
+        json["extensions"]["VRMC_vrm_animation"]["specVersion"] = "2.0".into();
+        let error = load_vrma(&json).err().ok_or("loaded version 2.0")?;
+        assert!(
+            error.contains("Unsupported spec version of VRMC_vrm_animation: 2.0"),
+            "{error}"
+        );
+
+        json["extensions"]["VRMC_vrm_animation"]["humanoid"]["humanBones"]["hips"]["node"] =
+            100.into();
+        json["extensions"]["VRMC_vrm_animation"]["specVersion"] = "1.0".into();
+        let error = load_vrma(&json).err().ok_or("loaded invalid node index")?;
+        assert!(error.contains("The node index 100"), "{error}");
+
+        json["extensions"] = serde_json::json!({});
+        let error = load_vrma(&json).err().ok_or("loaded without extension")?;
+        assert!(error.contains("Not found VRMC_vrm_animation"), "{error}");
+        success!()
+    }
+}